@@ -1,29 +1,78 @@
-use std::sync::Arc;
+mod bind_groups;
+mod buddy;
+mod buffers;
+mod compute;
+mod events;
+mod gpu_vec;
+mod heap;
+mod recipes;
+mod registry;
+mod shader_files;
+mod uniform_ring;
 
-use wgpu::{CreateSurfaceError, RequestDeviceError};
+use std::{error::Error, fmt::Display, sync::{Arc, Mutex}};
+
+use log::{info, warn};
+use wgpu::{CreateSurfaceError, RequestDeviceError, SurfaceError};
 use winit::window::Window;
 
-use crate::{rendering::Texture, HeatwaveConfig};
+use crate::{rendering::{FrameTexture, Texture}, HeatwaveConfig};
+
+pub use bind_groups::{BindGroupError, BindingResource};
+pub use buddy::{AllocatorStats, BuddyAllocator, SubAllocation};
+pub use buffers::BufferWriteError;
+pub use compute::{ComputeDispatch, ComputeError, ComputeReadback};
+pub use events::{GpuError, GpuErrorKind, GpuEvent};
+pub use gpu_vec::GpuVec;
+pub use heap::{BufferHeap, HeapAllocation, HeapError};
+pub use registry::{
+	BindGroupHandle, BufferHandle, ComputePipelineHandle, Handle, PipelineLayoutHandle, RawBufferHandle, Registry, RenderPipelineHandle, SamplerHandle, ShaderHandle, TextureHandle
+};
+pub use shader_files::{ShaderFileError, ShaderFileErrorKind};
+pub use uniform_ring::{UniformAllocation, UniformRingConfig, UniformRingError};
+pub(crate) use bind_groups::BindGroupRecipe;
+pub(crate) use buffers::write_buffer;
+pub(crate) use compute::submit_dispatch;
+pub(crate) use recipes::{BindGroupLayoutRecipe, BufferRecipe, ComputePipelineRecipe, LayoutRecipe, RenderPipelineRecipe, SamplerRecipe, ShaderRecipe};
+pub(crate) use shader_files::ShaderWatcher;
+pub(crate) use uniform_ring::UniformRing;
+
+use events::GpuEventQueue;
 
 ///Holds all relevant CPU objects for communication with the GPU
 pub struct GpuConnection<'window> {
-	//This is commented out as instance isn't currently needed outside of init. Comment this back in if instance is needed later down the line
-	//instance: wgpu::Instance,
-	//This is commented out as adapter isn't currently needed outside of init. Comment this back in if adapter is needed later down the line
-	//adapter: wgpu::Adapter,
-
-	///Reference to the presentable surface (Where the GPU draws to)
-	surface: wgpu::Surface<'window>,
-	///The configuration for the surface. Needed for future changes to the surface
+	///Kept so a new adapter can be requested if the device is lost
+	instance: wgpu::Instance,
+	///How the adapter and device were requested, so they can be requested again if the device is lost
+	settings: DeviceSettings,
+	///The physical device chosen for the connection. Kept for diagnostics
+	adapter: wgpu::Adapter,
+
+	///Where the GPU draws to. Either a presentable surface or an offscreen texture when headless
+	target: RenderTarget<'window>,
+	///The configuration for the surface. Needed for future changes to the surface.\
+	///When headless, this describes the offscreen texture instead.
 	surface_config: wgpu::SurfaceConfiguration,
+	///The present modes supported by the surface. Empty when headless
+	present_modes: Vec<wgpu::PresentMode>,
+	///Present changes requested from other threads, applied before the next frame
+	present_settings: PresentSettings,
 	///The connection to the physical graphics device.
-	device: wgpu::Device,
+	device: Arc<wgpu::Device>,
 	///Sends and executes command buffers on the GPU
 	queue: wgpu::Queue,
-	///The size of the current draw texture.
+	///Errors and device loss reported by the device's callbacks
+	events: GpuEventQueue,
+	///The size of the current draw texture. Either dimension may be 0 while the window is minimised
 	texture_size: winit::dpi::PhysicalSize<u32>,
 	///The texture used to maintain object depth,
-	depth_texture: crate::rendering::Texture
+	depth_texture: crate::rendering::Texture,
+	///The number of samples asked for in the config, kept so it can be checked against a new adapter if the device is lost
+	requested_sample_count: u32,
+	///The number of samples taken per pixel, after checking the requested count against the adapter
+	sample_count: u32,
+	///The multisampled texture frames are drawn to before being resolved into the surface or offscreen texture. `None` when MSAA is off
+	msaa_texture: Option<crate::rendering::Texture>
 }
 
 impl<'window> GpuConnection<'window> {
@@ -38,81 +87,419 @@ impl<'window> GpuConnection<'window> {
 	pub async fn new(window: Arc<Window>, config: &HeatwaveConfig<'_>) -> Result<Self, GpuConnectionError> 
 	{
 		let size = window.inner_size();
-
-		assert!(size.width > 0 && size.height > 0, "Window size has to be above 0!");
+		let settings = DeviceSettings::new(config, wgpu::Backends::PRIMARY);
 
 		let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-			backends: wgpu::Backends::PRIMARY,
+			backends: settings.backends,
 			..Default::default() //Allowing non-compliant adapters might be supported at a later date
 		});
- 
+
+		let mut context = ConnectionContext::new(&settings, Some(size));
+
 		let surface = match instance.create_surface(window.clone()) {
 			Ok(surface) => surface,
 			Err(error) => {
-				return Err(GpuConnectionError { inner: GpuConnectionErrorKind::SurfaceCreation(error) } );
+				return Err(GpuConnectionError::new(GpuConnectionErrorKind::SurfaceCreation(error), context));
 			}
 		};
 
-		let adapter = match instance.request_adapter(
-			&wgpu::RequestAdapterOptions {
-				power_preference: config.power_preference,
-				compatible_surface: Some(&surface),
-				force_fallback_adapter: false
-			}
-		).await {
-			Some(adapter) => adapter,
-			None => return Err(GpuConnectionError { inner: GpuConnectionErrorKind::CompatibleAdapterNotFound })
-		};
+		let adapter = select_adapter(&instance, &settings, Some(&surface)).await.map_err(|kind| GpuConnectionError::new(kind, context.clone()))?;
+		context.adapter_name = Some(adapter.get_info().name);
 
-		let future_device = adapter.request_device(
-			&wgpu::DeviceDescriptor {
-				required_features: config.features,
-				required_limits: wgpu::Limits::default(),
-				label: Some("Heatwave Adapter")
-			}, None
-		);
+		let future_device = request_device(&adapter, &settings);
 
 		let surface_capabilities = surface.get_capabilities(&adapter);
-		let surface_format = surface_capabilities.formats.iter()
-			.copied()
-			.find(|f| f.is_srgb()) 
-			.unwrap_or(surface_capabilities.formats[0]);
+		for warning in config.validate_surface(&surface_capabilities).warnings() {
+			warn!("Heatwave config: {warning}");
+		}
+		let surface_format = select_surface_format(&surface_capabilities.formats);
 
 		let surface_config = wgpu::SurfaceConfiguration {
 			usage: wgpu::TextureUsages::RENDER_ATTACHMENT, //This isn't configurable, since this is a rendering engine
 			format: surface_format,
-			width: size.width,
-			height: size.height,
-			present_mode: wgpu::PresentMode::AutoVsync, //VSync is always enabled for best visuals if supported.
-			alpha_mode: surface_capabilities.alpha_modes[0], //Take the default alpha type.
+			width: size.width.max(1), //The window may start minimised. The surface is configured properly on the first resize
+			height: size.height.max(1),
+			present_mode: resolve_present_mode(config.present_mode, &surface_capabilities.present_modes),
+			alpha_mode: select_alpha_mode(config.transparent, &surface_capabilities.alpha_modes),
 			view_formats: vec![], //Todo: I don't really understand this
-			desired_maximum_frame_latency: 2
+			desired_maximum_frame_latency: config.frame_latency.max(1)
 		};
 
-		let (device, queue) = match future_device.await {
-			Ok(tuple) => tuple,
-			Err(error) => { return Err(GpuConnectionError { inner: GpuConnectionErrorKind::DeviceRequest(error)}); }
-		};
+		let (device, queue) = future_device.await.map_err(|kind| GpuConnectionError::new(kind, context))?;
 
 		surface.configure(&device, &surface_config);
+		let sample_count = resolve_sample_count(config.msaa_samples, &adapter, &device, surface_format);
 
 
 		Ok(GpuConnection {
-			//instance,
-			//adapter,
-			depth_texture: Texture::create_depth_texture(&device, &surface_config, "Heatwave Depth"),
-			surface,
+			instance,
+			settings,
+			adapter,
+			depth_texture: Texture::create_depth_texture(&device, &surface_config, sample_count, "Heatwave Depth"),
+			requested_sample_count: config.msaa_samples,
+			sample_count,
+			msaa_texture: create_msaa_texture(&device, &surface_config, sample_count),
+			target: RenderTarget::Surface(surface),
+			present_modes: surface_capabilities.present_modes,
+			present_settings: PresentSettings::default(),
 			surface_config,
-			device,
+			events: GpuEventQueue::install(&device),
+			device: Arc::new(device),
 			queue,
 			texture_size: size,
 		})
 	}
+	///Creates a new GPU connection that isn't bound to any window.\
+	///Frames are drawn to an offscreen texture owned by the connection, sized by [`HeatwaveConfig::default_size`].
+	///
+	///If no hardware adapter can be found, the software/fallback adapter is used instead.
+	///
+	///# Errors
+	/// Errors if the offscreen texture would be empty\
+	/// May error if no adapter could be found, including the fallback adapter\
+	/// May error during requesting of a physical device
+	pub async fn new_headless(config: &HeatwaveConfig<'_>) -> Result<Self, GpuConnectionError> {
+		let size = config.default_size;
+		let settings = DeviceSettings::new(config, wgpu::Backends::all()); //GPU-less machines often only have a software GL or Vulkan implementation, so take anything available
+		if size.width == 0 || size.height == 0 {
+			return Err(GpuConnectionError::new(GpuConnectionErrorKind::EmptyOffscreenTarget, ConnectionContext::new(&settings, Some(size))));
+		}
+
+		let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+			backends: settings.backends,
+			..Default::default()
+		});
+
+		let mut context = ConnectionContext::new(&settings, Some(size));
+
+		let adapter = select_headless_adapter(&instance, &settings).await.map_err(|kind| GpuConnectionError::new(kind, context.clone()))?;
+		context.adapter_name = Some(adapter.get_info().name);
+
+		let (device, queue) = request_device(&adapter, &settings).await.map_err(|kind| GpuConnectionError::new(kind, context))?;
+
+		let surface_config = wgpu::SurfaceConfiguration {
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+			format: Texture::OFFSCREEN_FORMAT,
+			width: size.width,
+			height: size.height,
+			present_mode: config.present_mode, //Unused, as nothing is presented
+			alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+			view_formats: vec![],
+			desired_maximum_frame_latency: config.frame_latency.max(1)
+		};
+		let sample_count = resolve_sample_count(config.msaa_samples, &adapter, &device, surface_config.format);
+
+		Ok(GpuConnection {
+			instance,
+			settings,
+			adapter,
+			depth_texture: Texture::create_depth_texture(&device, &surface_config, sample_count, "Heatwave Depth"),
+			requested_sample_count: config.msaa_samples,
+			sample_count,
+			msaa_texture: create_msaa_texture(&device, &surface_config, sample_count),
+			target: RenderTarget::Offscreen(Texture::create_render_target(&device, &surface_config, "Heatwave Offscreen Target")),
+			present_modes: vec![],
+			present_settings: PresentSettings::default(),
+			surface_config,
+			events: GpuEventQueue::install(&device),
+			device: Arc::new(device),
+			queue,
+			texture_size: size,
+		})
+	}
+	///Requests a new device, replacing the current one. Used to recover after the device is lost.
+	///
+	///The adapter is picked again using the settings the connection was created with, as the old one may have gone away (such as an unplugged GPU).\
+	///The surface belongs to the instance rather than the device, so it survives and is reconfigured for the new device. Its format is kept if the new adapter supports it.
+	///
+	///Everything created with the old device, such as buffers and pipelines, has to be created again. [`HeatwaveApp::recover_device`](crate::HeatwaveApp::recover_device) does this for registered resources.
+	///
+	///# Errors
+	/// May error if no adapter that meets the requirements of the app is found, or if the device request fails. The old device is kept if this happens
+	pub async fn recreate_device(&mut self) -> Result<(), GpuConnectionError> {
+		let mut context = ConnectionContext::new(&self.settings, Some(self.texture_size));
+
+		let adapter = match &self.target {
+			RenderTarget::Surface(surface) => select_adapter(&self.instance, &self.settings, Some(surface)).await,
+			RenderTarget::Offscreen(_) => select_headless_adapter(&self.instance, &self.settings).await
+		}.map_err(|kind| GpuConnectionError::new(kind, context.clone()))?;
+		context.adapter_name = Some(adapter.get_info().name);
+
+		let (device, queue) = request_device(&adapter, &self.settings).await.map_err(|kind| GpuConnectionError::new(kind, context))?;
+
+		if let RenderTarget::Surface(surface) = &self.target {
+			let capabilities = surface.get_capabilities(&adapter);
+			if !capabilities.formats.contains(&self.surface_config.format) {
+				self.surface_config.format = select_surface_format(&capabilities.formats);
+			}
+			if !capabilities.alpha_modes.contains(&self.surface_config.alpha_mode) {
+				self.surface_config.alpha_mode = capabilities.alpha_modes[0];
+			}
+			self.surface_config.present_mode = resolve_present_mode(self.surface_config.present_mode, &capabilities.present_modes);
+			self.present_modes = capabilities.present_modes;
+		}
+
+		self.adapter = adapter;
+		self.events = GpuEventQueue::install(&device);
+		self.device = Arc::new(device);
+		self.queue = queue;
+
+		let max_dimension = self.device.limits().max_texture_dimension_2d;
+		self.surface_config.width = self.surface_config.width.min(max_dimension);
+		self.surface_config.height = self.surface_config.height.min(max_dimension);
+
+		if let RenderTarget::Offscreen(texture) = &mut self.target {
+			*texture = Texture::create_render_target(&self.device, &self.surface_config, "Heatwave Offscreen Target");
+		}
+		self.reconfigure_surface();
+		self.sample_count = resolve_sample_count(self.requested_sample_count, &self.adapter, &self.device, self.surface_config.format);
+		self.recreate_frame_textures();
+
+		Ok(())
+	}
+	///Takes every error and device loss reported by the device since this was last called, oldest first.
+	///
+	///The default event handler calls this before handling each event, and forwards what it finds to the presenter.
+	pub fn poll_events(&self) -> Vec<GpuEvent> {
+		self.events.drain()
+	}
+	///Resizes the surface (or offscreen texture) and depth texture to match the new size.
+	///
+	///A size of 0 in either dimension is allowed, as this happens when a window is minimised.\
+	///The render targets are left as they are in that case, and [`GpuConnection::is_minimised`] returns true until a non-zero size is given.
+	///
+	///Sizes larger than the device's maximum texture size are clamped.
+	pub fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
+		self.texture_size = new_size;
+		if self.is_minimised() {
+			return;
+		}
+
+		let max_dimension = self.device.limits().max_texture_dimension_2d;
+		self.surface_config.width = new_size.width.min(max_dimension);
+		self.surface_config.height = new_size.height.min(max_dimension);
+
+		match &mut self.target {
+			RenderTarget::Surface(surface) => surface.configure(&self.device, &self.surface_config),
+			RenderTarget::Offscreen(texture) => *texture = Texture::create_render_target(&self.device, &self.surface_config, "Heatwave Offscreen Target")
+		}
+		self.recreate_frame_textures();
+	}
+	///Changes how frames are presented to the surface, taking effect from the next frame.
+	///
+	///If the surface doesn't support the mode requested, a similar supported mode is used instead. Returns the mode that was applied.
+	///
+	///Has no visible effect when headless, as frames are never presented.
+	pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) -> wgpu::PresentMode {
+		self.surface_config.present_mode = match self.target {
+			RenderTarget::Surface(_) => resolve_present_mode(mode, &self.present_modes),
+			RenderTarget::Offscreen(_) => mode
+		};
+		self.reconfigure_surface();
+		self.surface_config.present_mode
+	}
+	///Changes how many frames the GPU may queue up ahead of the one being shown, taking effect from the next frame.\
+	///Lower values reduce input latency, at the cost of possibly stalling the CPU. Values below 1 are treated as 1.
+	pub fn set_frame_latency(&mut self, latency: u32) {
+		self.surface_config.desired_maximum_frame_latency = latency.max(1);
+		self.reconfigure_surface();
+	}
+	///Returns a thread safe handle that can change the present mode and frame latency while the app is running.\
+	///Changes made through the handle are applied by [`GpuConnection::apply_present_settings`], which the default event handler calls before each frame.
+	pub fn present_settings(&self) -> PresentSettings {
+		self.present_settings.clone()
+	}
+	///Applies any changes requested through [`GpuConnection::present_settings`] handles since this was last called
+	pub fn apply_present_settings(&mut self) {
+		let pending = std::mem::take(&mut *self.present_settings.pending.lock().expect("Present settings were poisoned"));
+
+		if let Some(latency) = pending.frame_latency {
+			self.surface_config.desired_maximum_frame_latency = latency.max(1);
+		}
+		match pending.present_mode {
+			Some(mode) => { self.set_present_mode(mode); } //Reconfigures for the latency as well
+			None if pending.frame_latency.is_some() => self.reconfigure_surface(),
+			None => {}
+		}
+	}
+	///Makes the depth and multisampled textures again, to match the size of the surface and the sample count
+	fn recreate_frame_textures(&mut self) {
+		self.depth_texture = Texture::create_depth_texture(&self.device, &self.surface_config, self.sample_count, "Heatwave Depth");
+		self.msaa_texture = create_msaa_texture(&self.device, &self.surface_config, self.sample_count);
+	}
+	fn reconfigure_surface(&self) {
+		if let RenderTarget::Surface(surface) = &self.target {
+			if !self.is_minimised() {
+				surface.configure(&self.device, &self.surface_config);
+			}
+		}
+	}
+	///Gets the next texture to draw a frame to.
+	///
+	///If the surface was lost or outdated, it is reconfigured and asked for a texture again.
+	///
+	///# Errors
+	/// Errors if the frame should be skipped, either because there is nothing to draw to or the surface couldn't provide a texture.\
+	/// Errors with [`FrameError::OutOfMemory`] if the GPU ran out of memory, which can't be recovered from.
+	pub fn acquire_frame(&self) -> Result<FrameTexture<'_>, FrameError> {
+		if self.is_minimised() {
+			return Err(FrameError::Minimised);
+		}
+
+		let surface = match &self.target {
+			RenderTarget::Surface(surface) => surface,
+			RenderTarget::Offscreen(texture) => return Ok(FrameTexture::Offscreen(texture))
+		};
+
+		match surface.get_current_texture() {
+			Ok(texture) => Ok(FrameTexture::Surface(texture)),
+			Err(SurfaceError::Timeout) => {
+				warn!("Timed out waiting for the next surface texture, skipping frame");
+				Err(FrameError::Timeout)
+			}
+			Err(SurfaceError::OutOfMemory) => Err(FrameError::OutOfMemory),
+			Err(error @ (SurfaceError::Lost | SurfaceError::Outdated)) => {
+				warn!("Surface is {}, reconfiguring", if error == SurfaceError::Lost { "lost" } else { "outdated" });
+				surface.configure(&self.device, &self.surface_config);
+
+				match surface.get_current_texture() {
+					Ok(texture) => Ok(FrameTexture::Surface(texture)),
+					Err(SurfaceError::OutOfMemory) => Err(FrameError::OutOfMemory),
+					Err(error) => {
+						warn!("Surface still unavailable after reconfiguring ({error}), skipping frame");
+						Err(FrameError::SurfaceUnavailable)
+					}
+				}
+			}
+		}
+	}
+	///Returns true if the last size given to this connection had a width or height of 0, meaning there is nothing to draw to
+	pub fn is_minimised(&self) -> bool {
+		self.texture_size.width == 0 || self.texture_size.height == 0
+	}
+	///Lists the adapters available on the backends given, such as for showing a GPU picker to the user.
+	///
+	///Always empty on WebGPU, as adapters can only be requested asynchronously there.
+	pub fn enumerate_adapters(backends: wgpu::Backends) -> Vec<wgpu::AdapterInfo> {
+		let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+			backends,
+			..Default::default()
+		});
+		instance.enumerate_adapters(backends).iter().map(wgpu::Adapter::get_info).collect()
+	}
+	pub fn adapter(&self) -> &wgpu::Adapter {
+		&self.adapter
+	}
+	///Information about the adapter in use, such as its name, backend and device type
+	pub fn adapter_info(&self) -> wgpu::AdapterInfo {
+		self.adapter.get_info()
+	}
+	///Every feature the adapter supports. See [`wgpu::Device::features`] for the features that are enabled
+	pub fn adapter_features(&self) -> wgpu::Features {
+		self.adapter.features()
+	}
+	///The best limits the adapter supports. See [`wgpu::Device::limits`] for the limits in use
+	pub fn adapter_limits(&self) -> wgpu::Limits {
+		self.adapter.limits()
+	}
+	///The capabilities of the adapter in relation to the WebGPU spec, for spotting downlevel hardware
+	pub fn downlevel_capabilities(&self) -> wgpu::DownlevelCapabilities {
+		self.adapter.get_downlevel_capabilities()
+	}
+	///The features enabled on the device. This includes every required feature, and any optional features the adapter supports
+	pub fn features(&self) -> wgpu::Features {
+		self.device.features()
+	}
+	///Returns true if every feature given is enabled on the device. Useful for picking code paths for optional features
+	pub fn has_features(&self, features: wgpu::Features) -> bool {
+		self.device.features().contains(features)
+	}
+	///The limits the device was created with
+	pub fn limits(&self) -> wgpu::Limits {
+		self.device.limits()
+	}
 	pub fn device(&self) -> &wgpu::Device {
 		&self.device
 	}
-	pub fn surface(&self) -> &wgpu::Surface {
-		&self.surface
+	///The device, shared so it can be polled from other threads
+	pub(crate) fn shared_device(&self) -> &Arc<wgpu::Device> {
+		&self.device
+	}
+	pub fn queue(&self) -> &wgpu::Queue {
+		&self.queue
+	}
+	///Returns the presentable surface, or `None` if this connection is headless
+	pub fn surface(&self) -> Option<&wgpu::Surface<'window>> {
+		match &self.target {
+			RenderTarget::Surface(surface) => Some(surface),
+			RenderTarget::Offscreen(_) => None
+		}
+	}
+	///Returns the texture frames are drawn to, or `None` if this connection draws to a surface
+	pub fn offscreen_texture(&self) -> Option<&Texture> {
+		match &self.target {
+			RenderTarget::Surface(_) => None,
+			RenderTarget::Offscreen(texture) => Some(texture)
+		}
+	}
+	pub fn target(&self) -> &RenderTarget<'window> {
+		&self.target
+	}
+	///Returns true if this connection draws to an offscreen texture rather than a window
+	pub fn is_headless(&self) -> bool {
+		matches!(self.target, RenderTarget::Offscreen(_))
+	}
+	///Copies the contents of the offscreen texture back to the CPU as tightly packed RGBA8 rows.
+	///
+	///Blocks until the GPU has finished all submitted work.\
+	///Returns `None` if this connection isn't headless, or if the copy couldn't be mapped.
+	pub fn read_offscreen_pixels(&self) -> Option<Vec<u8>> {
+		let texture = self.offscreen_texture()?;
+
+		let width = self.surface_config.width;
+		let height = self.surface_config.height;
+		let unpadded_row = width * 4;
+		let padded_row = unpadded_row.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+		let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Heatwave Offscreen Readback"),
+			size: (padded_row * height) as wgpu::BufferAddress,
+			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+			mapped_at_creation: false
+		});
+
+		let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+			label: Some("Heatwave Offscreen Readback Encoder")
+		});
+		encoder.copy_texture_to_buffer(
+			texture.texture.as_image_copy(),
+			wgpu::ImageCopyBuffer {
+				buffer: &staging,
+				layout: wgpu::ImageDataLayout {
+					offset: 0,
+					bytes_per_row: Some(padded_row),
+					rows_per_image: Some(height)
+				}
+			},
+			texture.texture.size()
+		);
+		self.queue.submit(Some(encoder.finish()));
+
+		let slice = staging.slice(..);
+		let (sender, receiver) = std::sync::mpsc::channel();
+		slice.map_async(wgpu::MapMode::Read, move |result| { let _ = sender.send(result); });
+		self.device.poll(wgpu::Maintain::Wait);
+		receiver.recv().ok()?.ok()?;
+
+		let mapped = slice.get_mapped_range();
+		let pixels = mapped.chunks(padded_row as usize)
+			.flat_map(|row| &row[..unpadded_row as usize])
+			.copied()
+			.collect();
+		drop(mapped);
+		staging.unmap();
+
+		Some(pixels)
 	}
 	pub fn surface_config(&self) -> &wgpu::SurfaceConfiguration {
 		&self.surface_config
@@ -120,19 +507,395 @@ impl<'window> GpuConnection<'window> {
 	pub fn depth_texture(&self) -> &Texture {
 		&self.depth_texture
 	}
+	///The number of samples taken per pixel of each frame. Pipelines drawn in the frame must use this as their multisample count
+	pub fn sample_count(&self) -> u32 {
+		self.sample_count
+	}
+	///The multisampled texture frames are drawn to before being resolved, or `None` if MSAA is off
+	pub fn msaa_texture(&self) -> Option<&Texture> {
+		self.msaa_texture.as_ref()
+	}
+	///The size of the texture frames are currently drawn to
+	pub fn texture_size(&self) -> winit::dpi::PhysicalSize<u32> {
+		self.texture_size
+	}
+}
+
+///Where a [`GpuConnection`] draws its frames to
+pub enum RenderTarget<'window> {
+	///A presentable surface bound to a window
+	Surface(wgpu::Surface<'window>),
+	///A texture owned by the connection. Used by headless connections
+	Offscreen(Texture)
+}
+
+///Negotiates the features and limits in the config against what the adapter supports, then requests a device with them
+async fn request_device(adapter: &wgpu::Adapter, config: &DeviceSettings) -> Result<(wgpu::Device, wgpu::Queue), GpuConnectionErrorKind> {
+	let adapter_features = adapter.features();
+
+	let missing_features = config.required_features - adapter_features;
+	if !missing_features.is_empty() {
+		return Err(GpuConnectionErrorKind::MissingFeatures(missing_features));
+	}
+	let unavailable_features = config.optional_features - adapter_features;
+	if !unavailable_features.is_empty() {
+		info!("Optional features {unavailable_features:?} aren't supported by the adapter, and won't be enabled");
+	}
+
+	let adapter_limits = adapter.limits();
+	let limits = config.limits.resolve(&adapter_limits);
+	let mut unsupported_limits = Vec::new();
+	limits.check_limits_with_fail_fn(&adapter_limits, false, |name, requested, allowed| {
+		unsupported_limits.push(UnsupportedLimit { name, requested, allowed });
+	});
+	if !unsupported_limits.is_empty() {
+		return Err(GpuConnectionErrorKind::UnsupportedLimits(unsupported_limits));
+	}
+
+	adapter.request_device(
+		&wgpu::DeviceDescriptor {
+			required_features: config.required_features | (config.optional_features & adapter_features),
+			required_limits: limits,
+			label: Some("Heatwave Adapter")
+		}, None
+	).await.map_err(GpuConnectionErrorKind::DeviceRequest)
+}
+
+///Which limits to request from the device
+#[derive(Clone, Debug, Default)]
+pub enum LimitsPreset {
+	///wgpu's default limits, which are supported by almost all modern hardware
+	#[default]
+	Default,
+	///Limits supported by downlevel hardware, roughly equivalent to GLES 3.1 and D3D11.\
+	///Texture size limits are raised to what the adapter supports
+	Downlevel,
+	///Limits supported by WebGL2. Texture size limits are raised to what the adapter supports
+	WebGl2,
+	///The best limits the adapter supports
+	Adapter,
+	///Exactly the limits given
+	Custom(wgpu::Limits)
+}
+impl LimitsPreset {
+	///Returns the limits this preset describes for an adapter with the limits given
+	pub fn resolve(&self, adapter_limits: &wgpu::Limits) -> wgpu::Limits {
+		match self {
+			LimitsPreset::Default => wgpu::Limits::default(),
+			LimitsPreset::Downlevel => wgpu::Limits::downlevel_defaults().using_resolution(adapter_limits.clone()),
+			LimitsPreset::WebGl2 => wgpu::Limits::downlevel_webgl2_defaults().using_resolution(adapter_limits.clone()),
+			LimitsPreset::Adapter => adapter_limits.clone(),
+			LimitsPreset::Custom(limits) => limits.clone()
+		}
+	}
+}
+
+///Picks an adapter from a list of available adapters by returning its index, or `None` if none of them are suitable
+pub type AdapterSelector = fn(&[wgpu::AdapterInfo]) -> Option<usize>;
+
+///Picks an adapter using the config's selector if one is set, otherwise lets wgpu pick one based on the power preference
+async fn select_adapter(instance: &wgpu::Instance, config: &DeviceSettings, surface: Option<&wgpu::Surface<'_>>) -> Result<wgpu::Adapter, GpuConnectionErrorKind> {
+	let adapter = match config.adapter_selector {
+		Some(selector) => {
+			#[allow(clippy::unnecessary_map_or)] //Option::is_none_or is newer than the Rust versions wgpu supports
+			let mut adapters: Vec<wgpu::Adapter> = instance.enumerate_adapters(config.backends)
+				.into_iter()
+				.filter(|adapter| surface.map_or(true, |surface| adapter.is_surface_supported(surface)))
+				.filter(|adapter| !config.force_fallback_adapter || adapter.get_info().device_type == wgpu::DeviceType::Cpu)
+				.collect();
+			let infos: Vec<wgpu::AdapterInfo> = adapters.iter().map(wgpu::Adapter::get_info).collect();
+
+			selector(&infos).filter(|&index| index < adapters.len()).map(|index| adapters.swap_remove(index))
+		}
+		None => instance.request_adapter(
+			&wgpu::RequestAdapterOptions {
+				power_preference: config.power_preference,
+				compatible_surface: surface,
+				force_fallback_adapter: config.force_fallback_adapter
+			}
+		).await
+	};
+
+	adapter.ok_or(GpuConnectionErrorKind::CompatibleAdapterNotFound)
+}
+///Picks an adapter like [`select_adapter`], but falls back to the software adapter if nothing else is found and no selector is set
+async fn select_headless_adapter(instance: &wgpu::Instance, config: &DeviceSettings) -> Result<wgpu::Adapter, GpuConnectionErrorKind> {
+	match select_adapter(instance, config, None).await {
+		Err(_) if config.adapter_selector.is_none() && !config.force_fallback_adapter => {
+			let fallback_config = DeviceSettings { force_fallback_adapter: true, ..config.clone() };
+			select_adapter(instance, &fallback_config, None).await
+		}
+		result => result
+	}
+}
+
+///The parts of a [`HeatwaveConfig`] used to pick an adapter and request a device
+#[derive(Clone, Debug)]
+struct DeviceSettings {
+	power_preference: wgpu::PowerPreference,
+	backends: wgpu::Backends,
+	force_fallback_adapter: bool,
+	adapter_selector: Option<AdapterSelector>,
+	required_features: wgpu::Features,
+	optional_features: wgpu::Features,
+	limits: LimitsPreset
+}
+impl DeviceSettings {
+	///Copies the settings out of the config, using `default_backends` if the config doesn't choose any
+	fn new(config: &HeatwaveConfig, default_backends: wgpu::Backends) -> Self {
+		DeviceSettings {
+			power_preference: config.power_preference,
+			backends: config.backends.unwrap_or(default_backends),
+			force_fallback_adapter: config.force_fallback_adapter,
+			adapter_selector: config.adapter_selector,
+			required_features: config.required_features,
+			optional_features: config.optional_features,
+			limits: config.limits.clone()
+		}
+	}
+}
+
+///Picks the highest sample count up to the one requested that the device supports for both the colour format and the depth format.\
+///Counts above 4 are only available if the device has `TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES` enabled
+fn resolve_sample_count(requested: u32, adapter: &wgpu::Adapter, device: &wgpu::Device, format: wgpu::TextureFormat) -> u32 {
+	let format_flags = |format: wgpu::TextureFormat| match device.features().contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES) {
+		true => adapter.get_texture_format_features(format).flags,
+		false => format.guaranteed_format_features(device.features()).flags
+	};
+	let colour = format_flags(format);
+	let depth = format_flags(Texture::DEPTH_FORMAT);
+
+	let supported = [8, 4, 2].into_iter()
+		.filter(|&count| count <= requested)
+		.find(|&count| colour.sample_count_supported(count) && depth.sample_count_supported(count))
+		.unwrap_or(1);
+	if supported != requested.max(1) {
+		warn!("{requested}x MSAA isn't supported for {format:?}, using {supported}x instead");
+	}
+	supported
+}
+
+///Creates the texture frames are drawn to before being resolved, if the sample count calls for one
+fn create_msaa_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32) -> Option<Texture> {
+	(sample_count > 1).then(|| Texture::create_multisampled_target(device, config, sample_count, "Heatwave Multisampled Target"))
+}
+
+///Picks an sRGB format if the surface supports one, otherwise the surface's preferred format
+fn select_surface_format(supported: &[wgpu::TextureFormat]) -> wgpu::TextureFormat {
+	supported.iter()
+		.copied()
+		.find(|format| format.is_srgb())
+		.unwrap_or(supported[0])
+}
+
+///Picks an alpha mode that blends with what's behind the window if the window is transparent and the surface allows it.\
+///Otherwise, takes the default alpha mode
+fn select_alpha_mode(transparent: bool, supported: &[wgpu::CompositeAlphaMode]) -> wgpu::CompositeAlphaMode {
+	use wgpu::CompositeAlphaMode;
+
+	let blending = [CompositeAlphaMode::PreMultiplied, CompositeAlphaMode::PostMultiplied, CompositeAlphaMode::Inherit];
+	supported.iter()
+		.copied()
+		.find(|mode| transparent && blending.contains(mode))
+		.unwrap_or(supported[0])
+}
+
+///Picks the closest present mode to the one requested that the surface supports
+fn resolve_present_mode(requested: wgpu::PresentMode, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+	use wgpu::PresentMode;
+
+	let fallback = match requested {
+		PresentMode::AutoVsync | PresentMode::AutoNoVsync => return requested, //wgpu resolves these itself
+		_ if supported.contains(&requested) => return requested,
+		PresentMode::Immediate | PresentMode::Mailbox => PresentMode::AutoNoVsync,
+		PresentMode::Fifo | PresentMode::FifoRelaxed => PresentMode::Fifo //Fifo is supported everywhere
+	};
+	warn!("Present mode {requested:?} isn't supported by the surface, falling back to {fallback:?}");
+	fallback
+}
+
+///A thread safe handle for changing how frames are presented while the app is running, such as from a settings menu.
+///
+///Obtained from [`HeatwaveApp::present_settings`](crate::HeatwaveApp::present_settings). Changes are applied before the next frame is drawn.
+#[derive(Clone, Default, Debug)]
+pub struct PresentSettings {
+	pending: Arc<Mutex<PendingPresentSettings>>
+}
+#[derive(Default, Debug)]
+struct PendingPresentSettings {
+	present_mode: Option<wgpu::PresentMode>,
+	frame_latency: Option<u32>
+}
+impl PresentSettings {
+	///Requests a new present mode. See [`GpuConnection::set_present_mode`]
+	pub fn set_present_mode(&self, mode: wgpu::PresentMode) {
+		self.pending.lock().expect("Present settings were poisoned").present_mode = Some(mode);
+	}
+	///Requests a new frame latency. See [`GpuConnection::set_frame_latency`]
+	pub fn set_frame_latency(&self, latency: u32) {
+		self.pending.lock().expect("Present settings were poisoned").frame_latency = Some(latency);
+	}
+}
+
+///Runs `create`, capturing any validation error it causes rather than passing it to the device's error handler.
+///
+///Errors can't be captured synchronously on the web, so they are left to the error handler there.
+pub(crate) fn capture_validation_error<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> Result<T, wgpu::Error> {
+	#[cfg(not(target_arch = "wasm32"))]
+	{
+		device.push_error_scope(wgpu::ErrorFilter::Validation);
+		let value = create();
+		match pollster::block_on(device.pop_error_scope()) {
+			Some(error) => Err(error),
+			None => Ok(value)
+		}
+	}
+	#[cfg(target_arch = "wasm32")]
+	{
+		let _ = device;
+		Ok(create())
+	}
+}
+
+///Describes why a frame couldn't be drawn
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FrameError {
+	///The window is minimised, so there is nothing to draw to. The frame is skipped
+	Minimised,
+	///The surface took too long to provide a texture. The frame is skipped
+	Timeout,
+	///The surface was lost or outdated, and couldn't provide a texture after being reconfigured. The frame is skipped
+	SurfaceUnavailable,
+	///The GPU ran out of memory. This is fatal, and the default event handler closes the app after the presenter is told
+	OutOfMemory
+}
+impl FrameError {
+	///Returns true if the app can't continue rendering after this error
+	pub fn is_fatal(&self) -> bool {
+		matches!(self, FrameError::OutOfMemory)
+	}
 }
+impl Display for FrameError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			FrameError::Minimised => write!(f, "The window is minimised"),
+			FrameError::Timeout => write!(f, "Timed out waiting for the next surface texture"),
+			FrameError::SurfaceUnavailable => write!(f, "The surface was lost and couldn't be recovered for this frame"),
+			FrameError::OutOfMemory => write!(f, "The GPU ran out of memory")
+		}
+	}
+}
+impl Error for FrameError {}
 
+///Describes why a [`GpuConnection`] couldn't be created, along with what was being requested at the time
+#[derive(Debug)]
 pub struct GpuConnectionError {
-	inner: GpuConnectionErrorKind
+	inner: GpuConnectionErrorKind,
+	context: ConnectionContext
 }
 impl GpuConnectionError {
+	fn new(inner: GpuConnectionErrorKind, context: ConnectionContext) -> Self {
+		GpuConnectionError { inner, context }
+	}
 	pub fn kind(&self) -> &GpuConnectionErrorKind {
 		&self.inner
 	}
+	///What was being requested when the error occurred
+	pub fn context(&self) -> &ConnectionContext {
+		&self.context
+	}
+}
+impl Display for GpuConnectionError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} (", self.inner)?;
+		if let Some(adapter_name) = &self.context.adapter_name {
+			write!(f, "adapter: {adapter_name}, ")?;
+		}
+		if let Some(size) = self.context.window_size {
+			write!(f, "window size: {}x{}, ", size.width, size.height)?;
+		}
+		write!(f, "requested features: {:?})", self.context.requested_features)
+	}
+}
+impl Error for GpuConnectionError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		self.inner.source()
+	}
+}
+///What was being requested from the GPU when a [`GpuConnectionError`] occurred
+#[derive(Clone, Debug)]
+pub struct ConnectionContext {
+	///The name of the adapter, if one had been chosen
+	pub adapter_name: Option<String>,
+	///Every feature requested, both required and optional
+	pub requested_features: wgpu::Features,
+	///The size of the window or offscreen texture being drawn to
+	pub window_size: Option<winit::dpi::PhysicalSize<u32>>
+}
+impl ConnectionContext {
+	fn new(config: &DeviceSettings, window_size: Option<winit::dpi::PhysicalSize<u32>>) -> Self {
+		ConnectionContext {
+			adapter_name: None,
+			requested_features: config.required_features | config.optional_features,
+			window_size
+		}
+	}
 }
 ///Describes errors thrown by a [`GpuConnection`]
+#[derive(Debug)]
 pub enum GpuConnectionErrorKind {
 	SurfaceCreation(CreateSurfaceError),
 	DeviceRequest(RequestDeviceError),
-	CompatibleAdapterNotFound
+	CompatibleAdapterNotFound,
+	///The adapter doesn't support these required features
+	MissingFeatures(wgpu::Features),
+	///The adapter can't meet these requested limits
+	UnsupportedLimits(Vec<UnsupportedLimit>),
+	///A headless connection was asked for an offscreen texture with no pixels
+	EmptyOffscreenTarget
+}
+impl Display for GpuConnectionErrorKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			GpuConnectionErrorKind::SurfaceCreation(_) => write!(f, "Failed to create a surface for the window"),
+			GpuConnectionErrorKind::DeviceRequest(_) => write!(f, "Failed to request a device from the adapter"),
+			GpuConnectionErrorKind::CompatibleAdapterNotFound => write!(f, "No compatible adapter was found"),
+			GpuConnectionErrorKind::MissingFeatures(features) => write!(f, "The adapter doesn't support the required features {features:?}"),
+			GpuConnectionErrorKind::UnsupportedLimits(limits) => {
+				write!(f, "The adapter can't meet the requested limits: ")?;
+				for (index, limit) in limits.iter().enumerate() {
+					if index > 0 {
+						write!(f, ", ")?;
+					}
+					write!(f, "{limit}")?;
+				}
+				Ok(())
+			}
+			GpuConnectionErrorKind::EmptyOffscreenTarget => write!(f, "The offscreen texture has to be at least 1x1")
+		}
+	}
+}
+impl Error for GpuConnectionErrorKind {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			GpuConnectionErrorKind::SurfaceCreation(error) => Some(error),
+			GpuConnectionErrorKind::DeviceRequest(error) => Some(error),
+			_ => None
+		}
+	}
+}
+///A limit requested from a device that the adapter can't meet
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UnsupportedLimit {
+	///The name of the limit, matching the field of [`wgpu::Limits`]
+	pub name: &'static str,
+	///The value that was requested
+	pub requested: u64,
+	///The best value the adapter supports
+	pub allowed: u64
+}
+impl Display for UnsupportedLimit {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} (requested {}, adapter allows {})", self.name, self.requested, self.allowed)
+	}
 }
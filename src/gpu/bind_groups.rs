@@ -0,0 +1,123 @@
+use std::{error::Error, fmt::Display};
+
+use crate::rendering::Texture;
+
+use super::{capture_validation_error, recipes::BindGroupLayoutRecipe, BindGroupHandle, RawBufferHandle, Registry, SamplerHandle, TextureHandle};
+
+///A registered resource to bind to an entry of a bind group
+///
+///# Usage
+///```rs
+/// let material = my_heatwave_app.add_bind_group(0, &[
+///     BindingResource::TextureView(my_texture),
+///     BindingResource::Sampler(my_sampler),
+///     BindingResource::Buffer(my_uniform_buffer.raw())
+/// ])?;
+///```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BindingResource {
+	///The whole of a registered buffer
+	Buffer(RawBufferHandle),
+	///Part of a registered buffer. A size of `None` binds the rest of the buffer after the offset
+	BufferRange { buffer: RawBufferHandle, offset: wgpu::BufferAddress, size: Option<wgpu::BufferSize> },
+	///The view of a registered texture
+	TextureView(TextureHandle),
+	///The sampler that belongs to a registered texture
+	TextureSampler(TextureHandle),
+	///A registered sampler
+	Sampler(SamplerHandle)
+}
+
+///The resources a registered bind group was made from, so it can be made again
+pub(crate) struct BindGroupRecipe {
+	///The index of the app's bind group layout the group was made for. This is also the index the group is bound to
+	pub(crate) layout: usize,
+	pub(crate) resources: Vec<BindingResource>
+}
+impl BindGroupRecipe {
+	///Returns true if any of the resources are the buffer given
+	pub(crate) fn uses_buffer(&self, handle: RawBufferHandle) -> bool {
+		self.resources.iter().any(|resource| match *resource {
+			BindingResource::Buffer(buffer) | BindingResource::BufferRange { buffer, .. } => buffer == handle,
+			_ => false
+		})
+	}
+	///Creates the bind group, binding each resource to the layout entry at the same position
+	pub(crate) fn create(
+		&self,
+		device: &wgpu::Device,
+		layouts: &[(wgpu::BindGroupLayout, BindGroupLayoutRecipe)],
+		buffers: &Registry<wgpu::Buffer>,
+		textures: &Registry<Texture>,
+		samplers: &Registry<wgpu::Sampler>
+	) -> Result<wgpu::BindGroup, BindGroupError> {
+		let (layout, layout_recipe) = layouts.get(self.layout).ok_or(BindGroupError::UnknownLayout(self.layout))?;
+		if layout_recipe.entries().len() != self.resources.len() {
+			return Err(BindGroupError::WrongResourceCount { expected: layout_recipe.entries().len(), found: self.resources.len() });
+		}
+
+		let entries = layout_recipe.entries().iter().zip(&self.resources).map(|(layout_entry, resource)| {
+			let resource = match *resource {
+				BindingResource::Buffer(handle) => buffers.get(handle).ok_or(BindGroupError::UnknownBuffer(handle))?.as_entire_binding(),
+				BindingResource::BufferRange { buffer, offset, size } => wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+					buffer: buffers.get(buffer).ok_or(BindGroupError::UnknownBuffer(buffer))?,
+					offset,
+					size
+				}),
+				BindingResource::TextureView(handle) => wgpu::BindingResource::TextureView(&textures.get(handle).ok_or(BindGroupError::UnknownTexture(handle))?.view),
+				BindingResource::TextureSampler(handle) => wgpu::BindingResource::Sampler(&textures.get(handle).ok_or(BindGroupError::UnknownTexture(handle))?.sampler),
+				BindingResource::Sampler(handle) => wgpu::BindingResource::Sampler(samplers.get(handle).ok_or(BindGroupError::UnknownSampler(handle))?)
+			};
+			Ok(wgpu::BindGroupEntry {
+				binding: layout_entry.binding,
+				resource
+			})
+		}).collect::<Result<Vec<_>, BindGroupError>>()?;
+
+		capture_validation_error(device, || device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Heatwave Bind Group"),
+			layout,
+			entries: &entries
+		})).map_err(BindGroupError::Rejected)
+	}
+}
+
+///Describes why a bind group couldn't be made or bound
+#[derive(Debug)]
+pub enum BindGroupError {
+	///The config has no bind group layout at this index
+	UnknownLayout(usize),
+	///The bind group doesn't exist, or was removed
+	UnknownBindGroup(BindGroupHandle),
+	///The buffer doesn't exist, or was removed
+	UnknownBuffer(RawBufferHandle),
+	///The texture doesn't exist, or was removed
+	UnknownTexture(TextureHandle),
+	///The sampler doesn't exist, or was removed
+	UnknownSampler(SamplerHandle),
+	///The layout has a different number of entries to the number of resources given
+	WrongResourceCount { expected: usize, found: usize },
+	///wgpu rejected the bind group, such as when a resource doesn't match the type of its layout entry
+	Rejected(wgpu::Error)
+}
+impl Display for BindGroupError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BindGroupError::UnknownLayout(index) => write!(f, "No bind group layout exists at index {index}"),
+			BindGroupError::UnknownBindGroup(handle) => write!(f, "No bind group exists for {handle:?}"),
+			BindGroupError::UnknownBuffer(handle) => write!(f, "No buffer exists for {handle:?}"),
+			BindGroupError::UnknownTexture(handle) => write!(f, "No texture exists for {handle:?}"),
+			BindGroupError::UnknownSampler(handle) => write!(f, "No sampler exists for {handle:?}"),
+			BindGroupError::WrongResourceCount { expected, found } => write!(f, "The layout has {expected} entries, but {found} resources were given"),
+			BindGroupError::Rejected(_) => write!(f, "wgpu rejected the bind group")
+		}
+	}
+}
+impl Error for BindGroupError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			BindGroupError::Rejected(error) => Some(error),
+			_ => None
+		}
+	}
+}
@@ -0,0 +1,152 @@
+use std::collections::{BTreeSet, HashMap};
+
+///Hands out ranges of a fixed size space using the buddy system. Knows nothing about the GPU, so it can manage any kind of memory.
+///
+///Every allocation takes a block with a power of two size, which is split from a larger block when needed and merged back with its buddy when freed.\
+///Blocks are aligned to their size, so any alignment up to the block size comes for free.
+///
+///Allocation is deterministic, always taking the lowest free offset of the smallest block that fits.
+pub struct BuddyAllocator {
+	size: u64,
+	min_block: u64,
+	///The free blocks of each order, by offset. A block of order `n` is `min_block << n` bytes
+	free: Vec<BTreeSet<u64>>,
+	///The order of each allocated block, and the size that was asked for, by offset
+	allocated: HashMap<u64, (usize, u64)>,
+	allocated_bytes: u64,
+	requested_bytes: u64
+}
+///A range handed out by a [`BuddyAllocator`]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SubAllocation {
+	///The start of the range
+	pub offset: u64,
+	///The size that was asked for. The block behind it may be bigger
+	pub size: u64
+}
+///How much of an allocator's space is used
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub struct AllocatorStats {
+	///The size of the space managed
+	pub capacity: u64,
+	///The space taken by allocated blocks, including what was rounded up
+	pub allocated: u64,
+	///The space that was asked for. The difference from `allocated` is lost to rounding up to block sizes
+	pub requested: u64,
+	///The space not taken by any block
+	pub free: u64,
+	///The size of the largest free block, which is the largest allocation that could be made
+	pub largest_free_block: u64,
+	///The number of live allocations
+	pub allocations: usize
+}
+impl AllocatorStats {
+	///How scattered the free space is, from 0 (all of it is in one block) to nearly 1 (it's spread across many small blocks)
+	pub fn fragmentation(&self) -> f32 {
+		if self.free == 0 {
+			return 0.0;
+		}
+		1.0 - self.largest_free_block as f32 / self.free as f32
+	}
+	///Adds the stats of another allocator to these
+	pub(crate) fn combine(self, other: AllocatorStats) -> AllocatorStats {
+		AllocatorStats {
+			capacity: self.capacity + other.capacity,
+			allocated: self.allocated + other.allocated,
+			requested: self.requested + other.requested,
+			free: self.free + other.free,
+			largest_free_block: self.largest_free_block.max(other.largest_free_block),
+			allocations: self.allocations + other.allocations
+		}
+	}
+}
+impl BuddyAllocator {
+	///Creates an allocator for `size` bytes, where the smallest block handed out is `min_block` bytes
+	///
+	///# Panics
+	/// Panics if `size` or `min_block` isn't a power of two, or if `min_block` is larger than `size`
+	pub fn new(size: u64, min_block: u64) -> Self {
+		assert!(size.is_power_of_two() && min_block.is_power_of_two(), "Expected the size and minimum block of a buddy allocator to be powers of two");
+		assert!(min_block <= size, "Expected the minimum block of a buddy allocator to fit in its size");
+
+		let orders = (size / min_block).trailing_zeros() as usize + 1;
+		let mut free = vec![BTreeSet::new(); orders];
+		free[orders - 1].insert(0);
+
+		BuddyAllocator {
+			size,
+			min_block,
+			free,
+			allocated: HashMap::new(),
+			allocated_bytes: 0,
+			requested_bytes: 0
+		}
+	}
+
+	///Takes a range of at least `size` bytes, starting at a multiple of `alignment`.\
+	///Returns `None` if there's no free block big enough, or if `alignment` isn't a power of two
+	pub fn allocate(&mut self, size: u64, alignment: u64) -> Option<SubAllocation> {
+		if !alignment.is_power_of_two() {
+			return None;
+		}
+		let block_size = size.max(alignment).max(self.min_block).checked_next_power_of_two()?;
+		if block_size > self.size {
+			return None;
+		}
+		let order = (block_size / self.min_block).trailing_zeros() as usize;
+
+		let found = (order..self.free.len()).find(|&found| !self.free[found].is_empty())?;
+		let offset = self.free[found].pop_first().expect("Expected a free block in a non-empty free list");
+		for split in (order..found).rev() {
+			self.free[split].insert(offset + (self.min_block << split)); //The upper half becomes free, while the lower half keeps being split
+		}
+
+		self.allocated.insert(offset, (order, size));
+		self.allocated_bytes += block_size;
+		self.requested_bytes += size;
+		Some(SubAllocation { offset, size })
+	}
+	///Gives the allocation's block back, merging it with its buddy while the buddy is free.\
+	///Returns false if the allocation wasn't made by this allocator, or was already freed. An allocation whose size doesn't match the block at its offset is also rejected,
+	///so a stale allocation can't free a block that was handed out again
+	pub fn free(&mut self, allocation: SubAllocation) -> bool {
+		let Some(&(mut order, requested)) = self.allocated.get(&allocation.offset).filter(|&&(_, requested)| requested == allocation.size) else {
+			return false;
+		};
+		self.allocated.remove(&allocation.offset);
+		self.allocated_bytes -= self.min_block << order;
+		self.requested_bytes -= requested;
+
+		let mut offset = allocation.offset;
+		while order + 1 < self.free.len() {
+			let buddy = offset ^ (self.min_block << order);
+			if !self.free[order].remove(&buddy) {
+				break;
+			}
+			offset = offset.min(buddy);
+			order += 1;
+		}
+		self.free[order].insert(offset);
+		true
+	}
+
+	///The size of the space managed
+	pub fn capacity(&self) -> u64 {
+		self.size
+	}
+	///Returns true if nothing is allocated
+	pub fn is_empty(&self) -> bool {
+		self.allocated.is_empty()
+	}
+	///How much of the space is used, and how scattered the free space is
+	pub fn stats(&self) -> AllocatorStats {
+		AllocatorStats {
+			capacity: self.size,
+			allocated: self.allocated_bytes,
+			requested: self.requested_bytes,
+			free: self.size - self.allocated_bytes,
+			largest_free_block: self.free.iter().rposition(|blocks| !blocks.is_empty()).map_or(0, |order| self.min_block << order),
+			allocations: self.allocated.len()
+		}
+	}
+}
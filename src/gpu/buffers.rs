@@ -0,0 +1,57 @@
+use std::{error::Error, fmt::Display};
+
+use super::{RawBufferHandle, Registry};
+
+///Describes why a buffer couldn't be written to
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BufferWriteError {
+	///The buffer doesn't exist, or was removed
+	UnknownBuffer(RawBufferHandle),
+	///The buffer wasn't created with [`wgpu::BufferUsages::COPY_DST`]
+	NotWritable(RawBufferHandle),
+	///The write would go past the end of the buffer. Sizes are in bytes
+	OutOfRange { offset: wgpu::BufferAddress, size: wgpu::BufferAddress, buffer_size: wgpu::BufferAddress },
+	///The offset or size of the write isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]. Sizes are in bytes
+	Misaligned { offset: wgpu::BufferAddress, size: wgpu::BufferAddress }
+}
+impl Display for BufferWriteError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			BufferWriteError::UnknownBuffer(handle) => write!(f, "No buffer exists for {handle:?}"),
+			BufferWriteError::NotWritable(handle) => write!(f, "The buffer {handle:?} wasn't created with COPY_DST usage"),
+			BufferWriteError::OutOfRange { offset, size, buffer_size } => write!(f, "Writing {size} bytes at offset {offset} goes past the end of a {buffer_size} byte buffer"),
+			BufferWriteError::Misaligned { offset, size } => write!(f, "Writing {size} bytes at offset {offset} isn't aligned to {} bytes", wgpu::COPY_BUFFER_ALIGNMENT)
+		}
+	}
+}
+impl Error for BufferWriteError {}
+
+///Queues the bytes to be written to the buffer, checking the write is valid first so wgpu doesn't report it as an uncaptured error.
+///
+///The write begins at element `first_element`, where each element is `element_size` bytes
+#[allow(clippy::unnecessary_map_or, clippy::manual_is_multiple_of)] //Option::is_none_or and u64::is_multiple_of are newer than the Rust versions wgpu supports
+pub(crate) fn write_buffer(
+	queue: &wgpu::Queue,
+	buffers: &Registry<wgpu::Buffer>,
+	handle: RawBufferHandle,
+	first_element: usize,
+	element_size: wgpu::BufferAddress,
+	data: &[u8]
+) -> Result<(), BufferWriteError> {
+	let buffer = buffers.get(handle).ok_or(BufferWriteError::UnknownBuffer(handle))?;
+	if !buffer.usage().contains(wgpu::BufferUsages::COPY_DST) {
+		return Err(BufferWriteError::NotWritable(handle));
+	}
+
+	let size = data.len() as wgpu::BufferAddress;
+	let offset = (first_element as wgpu::BufferAddress).checked_mul(element_size).ok_or(BufferWriteError::OutOfRange { offset: wgpu::BufferAddress::MAX, size, buffer_size: buffer.size() })?;
+	if offset.checked_add(size).map_or(true, |end| end > buffer.size()) {
+		return Err(BufferWriteError::OutOfRange { offset, size, buffer_size: buffer.size() });
+	}
+	if offset % wgpu::COPY_BUFFER_ALIGNMENT != 0 || size % wgpu::COPY_BUFFER_ALIGNMENT != 0 {
+		return Err(BufferWriteError::Misaligned { offset, size });
+	}
+
+	queue.write_buffer(buffer, offset, data);
+	Ok(())
+}
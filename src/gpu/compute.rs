@@ -0,0 +1,225 @@
+use std::{
+	error::Error,
+	fmt::Display,
+	future::Future,
+	marker::PhantomData,
+	pin::Pin,
+	sync::{Arc, Mutex},
+	task::{Context, Poll, Waker}
+};
+
+use wgpu::BufferAsyncError;
+
+use super::{ComputePipelineHandle, RawBufferHandle, Registry};
+
+///Describes a single compute dispatch
+///
+///# Usage
+///```rs
+/// let dispatch = ComputeDispatch {
+///     bind_groups: &[&my_bind_group],
+///     ..ComputeDispatch::new(my_pipeline_handle, [64, 1, 1])
+/// };
+///
+/// let results: Vec<f32> = my_heatwave_app.compute(&dispatch, my_storage_buffer_handle)?;
+///```
+#[derive(Clone, Debug)]
+pub struct ComputeDispatch<'a> {
+	///The compute pipeline to run
+	pub pipeline: ComputePipelineHandle,
+	///The bind groups to use. Each group is bound to the index of its position in the slice
+	pub bind_groups: &'a [&'a wgpu::BindGroup],
+	///The number of workgroups to dispatch in the x, y and z dimensions
+	pub workgroups: [u32; 3]
+}
+impl ComputeDispatch<'_> {
+	///Creates a dispatch for the pipeline with no bind groups
+	pub fn new(pipeline: ComputePipelineHandle, workgroups: [u32; 3]) -> Self {
+		ComputeDispatch {
+			pipeline,
+			bind_groups: &[],
+			workgroups
+		}
+	}
+}
+
+///Describes why a compute dispatch or its readback failed
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ComputeError {
+	///The compute pipeline doesn't exist, or was removed
+	UnknownPipeline(ComputePipelineHandle),
+	///The buffer doesn't exist, or was removed
+	UnknownBuffer(RawBufferHandle),
+	///The buffer results were to be read from wasn't created with [`wgpu::BufferUsages::COPY_SRC`]
+	OutputNotCopyable(RawBufferHandle),
+	///The buffer results were to be read from isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`] bytes long, so can't be copied from
+	UnalignedOutput { handle: RawBufferHandle, size: wgpu::BufferAddress },
+	///The staging buffer couldn't be mapped for reading
+	Map(BufferAsyncError)
+}
+impl Display for ComputeError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ComputeError::UnknownPipeline(handle) => write!(f, "No compute pipeline exists for {handle:?}"),
+			ComputeError::UnknownBuffer(handle) => write!(f, "No buffer exists for {handle:?}"),
+			ComputeError::OutputNotCopyable(handle) => write!(f, "The output buffer {handle:?} wasn't created with COPY_SRC usage"),
+			ComputeError::UnalignedOutput { handle, size } => write!(f, "The output buffer {handle:?} is {size} bytes, which isn't a multiple of {} bytes, so can't be copied", wgpu::COPY_BUFFER_ALIGNMENT),
+			ComputeError::Map(_) => write!(f, "Failed to map the results for reading")
+		}
+	}
+}
+impl Error for ComputeError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			ComputeError::Map(error) => Some(error),
+			_ => None
+		}
+	}
+}
+
+///Records and submits the dispatch. If an output buffer is given, its contents are copied into a new staging buffer, which is returned.
+pub(crate) fn submit_dispatch(
+	device: &wgpu::Device,
+	queue: &wgpu::Queue,
+	pipelines: &Registry<wgpu::ComputePipeline>,
+	buffers: &Registry<wgpu::Buffer>,
+	dispatch: &ComputeDispatch,
+	output: Option<RawBufferHandle>
+) -> Result<Option<wgpu::Buffer>, ComputeError> {
+	let pipeline = pipelines.get(dispatch.pipeline).ok_or(ComputeError::UnknownPipeline(dispatch.pipeline))?;
+	let output = match output {
+		Some(handle) => {
+			let buffer = buffers.get(handle).ok_or(ComputeError::UnknownBuffer(handle))?;
+			if !buffer.usage().contains(wgpu::BufferUsages::COPY_SRC) {
+				return Err(ComputeError::OutputNotCopyable(handle));
+			}
+			//Copies have to cover whole words, and can't read past the end of the buffer to round up
+			if buffer.size() % wgpu::COPY_BUFFER_ALIGNMENT != 0 {
+				return Err(ComputeError::UnalignedOutput { handle, size: buffer.size() });
+			}
+			Some(buffer)
+		}
+		None => None
+	};
+
+	let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+		label: Some("Heatwave Compute Encoder")
+	});
+
+	{
+		let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+			label: Some("Heatwave Compute Pass"),
+			timestamp_writes: None
+		});
+		compute_pass.set_pipeline(pipeline);
+		for (index, bind_group) in dispatch.bind_groups.iter().enumerate() {
+			compute_pass.set_bind_group(index as u32, bind_group, &[]);
+		}
+		let [x, y, z] = dispatch.workgroups;
+		compute_pass.dispatch_workgroups(x, y, z);
+	}
+
+	let staging = output.map(|output| {
+		let staging = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Heatwave Compute Readback"),
+			size: output.size(),
+			usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+			mapped_at_creation: false
+		});
+		encoder.copy_buffer_to_buffer(output, 0, &staging, 0, output.size());
+		staging
+	});
+
+	queue.submit(Some(encoder.finish()));
+
+	Ok(staging)
+}
+
+///Resolves to the contents of a staging buffer once the GPU has finished writing to it.
+///
+///Returned by the async compute functions. On native platforms, map callbacks only run while the device is polled,
+///so while this future is pending a helper thread waits on the device and wakes the task once the work is done.
+pub struct ComputeReadback<'a, T> {
+	device: Arc<wgpu::Device>,
+	staging: wgpu::Buffer,
+	state: Arc<Mutex<MapState>>,
+	_marker: PhantomData<(&'a wgpu::Device, T)>
+}
+#[derive(Default)]
+struct MapState {
+	result: Option<Result<(), BufferAsyncError>>,
+	waker: Option<Waker>,
+	///Whether a helper thread is currently waiting on the device for this readback
+	polling: bool
+}
+impl<'a, T: bytemuck::Pod> ComputeReadback<'a, T> {
+	pub(crate) fn new(device: &'a Arc<wgpu::Device>, staging: wgpu::Buffer) -> Self {
+		let state = Arc::new(Mutex::new(MapState::default()));
+
+		let callback_state = state.clone();
+		staging.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+			let mut state = callback_state.lock().expect("Readback state was poisoned");
+			state.result = Some(result);
+			if let Some(waker) = state.waker.take() {
+				waker.wake();
+			}
+		});
+
+		ComputeReadback {
+			device: device.clone(),
+			staging,
+			state,
+			_marker: PhantomData
+		}
+	}
+	///Blocks until the readback is complete
+	pub(crate) fn block(self) -> Result<Vec<T>, ComputeError> {
+		self.device.poll(wgpu::Maintain::Wait);
+		pollster::block_on(self)
+	}
+}
+impl<T: bytemuck::Pod> Future for ComputeReadback<'_, T> {
+	type Output = Result<Vec<T>, ComputeError>;
+
+	fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		//Native platforms only run map callbacks while the device is being polled. This is a no-op on the web
+		self.device.poll(wgpu::Maintain::Poll);
+
+		let mut state = self.state.lock().expect("Readback state was poisoned");
+		match state.result.take() {
+			Some(Ok(())) => {
+				drop(state);
+				let data = bytemuck::pod_collect_to_vec(&self.staging.slice(..).get_mapped_range());
+				self.staging.unmap();
+				Poll::Ready(Ok(data))
+			}
+			Some(Err(error)) => Poll::Ready(Err(ComputeError::Map(error))),
+			None => {
+				state.waker = Some(cx.waker().clone());
+				//Nothing else will poll the device on native, so wait on it from another thread instead of spinning
+				#[cfg(not(target_arch = "wasm32"))]
+				if !state.polling {
+					state.polling = true;
+					spawn_poll_thread(self.device.clone(), self.state.clone());
+				}
+				Poll::Pending
+			}
+		}
+	}
+}
+///Waits for the device to finish its submitted work, which runs the map callback and wakes the readback.\
+///If the callback hasn't run by then, such as when it was mapped after the wait started, the readback is woken anyway so it can poll again
+#[cfg(not(target_arch = "wasm32"))]
+fn spawn_poll_thread(device: Arc<wgpu::Device>, state: Arc<Mutex<MapState>>) {
+	std::thread::spawn(move || {
+		device.poll(wgpu::Maintain::Wait);
+
+		let mut state = state.lock().expect("Readback state was poisoned");
+		state.polling = false;
+		if state.result.is_none() {
+			if let Some(waker) = state.waker.take() {
+				waker.wake();
+			}
+		}
+	});
+}
@@ -0,0 +1,82 @@
+use std::{
+	error::Error,
+	fmt::Display,
+	sync::{mpsc::{channel, Receiver}, Mutex}
+};
+
+use log::error;
+
+use crate::DeviceLostError;
+
+///Something reported by the GPU outside of any call that could return it, such as an error in submitted work or the device being lost
+#[derive(Clone, PartialEq, Debug)]
+pub enum GpuEvent {
+	///wgpu reported an error that wasn't captured by an error scope
+	Error(GpuError),
+	///The device was lost, and can't be used anymore
+	DeviceLost(DeviceLostError)
+}
+
+///An error reported by wgpu that wasn't captured by an error scope. Without a handler, wgpu would panic on these
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct GpuError {
+	///What kind of error wgpu reported
+	pub kind: GpuErrorKind,
+	///The description wgpu gave for the error
+	pub message: String
+}
+///The kind of error wgpu reported
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GpuErrorKind {
+	///The GPU ran out of memory while creating a resource
+	OutOfMemory,
+	///A call broke one of wgpu's rules, such as using a buffer without the usage it needs
+	Validation
+}
+impl From<wgpu::Error> for GpuError {
+	fn from(value: wgpu::Error) -> Self {
+		match value {
+			wgpu::Error::OutOfMemory { source } => GpuError { kind: GpuErrorKind::OutOfMemory, message: source.to_string() },
+			wgpu::Error::Validation { description, .. } => GpuError { kind: GpuErrorKind::Validation, message: description }
+		}
+	}
+}
+impl Display for GpuError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self.kind {
+			GpuErrorKind::OutOfMemory => write!(f, "The GPU ran out of memory: {}", self.message),
+			GpuErrorKind::Validation => write!(f, "GPU validation error: {}", self.message)
+		}
+	}
+}
+impl Error for GpuError {}
+
+///Collects the errors and device loss reported by a device's callbacks, so they can be handled on the thread that owns the device
+pub(crate) struct GpuEventQueue {
+	//Wrapped so the connection can still be shared between threads
+	receiver: Mutex<Receiver<GpuEvent>>
+}
+impl GpuEventQueue {
+	///Installs an uncaptured error handler and a device lost callback on the device, both of which send what they receive to the queue
+	pub(crate) fn install(device: &wgpu::Device) -> Self {
+		let (sender, receiver) = channel();
+
+		let error_sender = sender.clone();
+		device.on_uncaptured_error(Box::new(move |error| {
+			let error = GpuError::from(error);
+			error!("Uncaptured GPU error: {error}");
+			let _ = error_sender.send(GpuEvent::Error(error)); //The queue is only dropped alongside the device
+		}));
+		device.set_device_lost_callback(move |reason, message| {
+			let _ = sender.send(GpuEvent::DeviceLost(DeviceLostError { reason, message }));
+		});
+
+		GpuEventQueue {
+			receiver: Mutex::new(receiver)
+		}
+	}
+	///Takes every event reported since this was last called, oldest first
+	pub(crate) fn drain(&self) -> Vec<GpuEvent> {
+		self.receiver.lock().expect("GPU event queue was poisoned").try_iter().collect()
+	}
+}
@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use crate::{rendering::ShaderObject, HeatwaveApp};
+
+use super::{BufferHandle, BufferWriteError};
+
+///A growable list of `T` kept in a registered buffer, like a [`Vec`] on the GPU.
+///
+///Changes are made to a copy of the list kept on the CPU, then [`GpuVec::sync`] uploads the elements that changed since the last sync.\
+///If the list outgrew the buffer, the buffer is reallocated with room to spare, keeping its handle. The old contents are copied across on the GPU, so anything a shader wrote to the buffer is kept.
+///
+///Bind groups made from the buffer with [`HeatwaveApp::add_bind_group`] are made again when it's reallocated. Bind groups made by hand need to be made again by the user.
+///
+///# Usage
+///```rs
+/// let mut instances = GpuVec::new(&mut my_heatwave_app, Some("Instances"), wgpu::BufferUsages::VERTEX, 64);
+/// instances.push(my_instance);
+/// instances.extend(more_instances);
+/// instances.sync(&mut my_heatwave_app)?;
+///
+/// let draw = DrawCall {
+///     vertex_buffers: &[my_vertex_buffer.raw(), instances.handle().raw()],
+///     instances: 0..instances.len() as u32,
+///     ..DrawCall::new(my_pipeline, 0..vertex_count)
+/// };
+///```
+pub struct GpuVec<T: bytemuck::Pod> {
+	handle: BufferHandle<T>,
+	values: Vec<T>,
+	///The number of elements the buffer has room for
+	capacity: usize,
+	///The elements changed since the last sync
+	dirty: Option<Range<usize>>
+}
+impl<T: bytemuck::Pod> GpuVec<T> {
+	///Creates an empty list, registering a buffer with room for `capacity` elements.
+	///
+	///[`wgpu::BufferUsages::COPY_SRC`] and [`wgpu::BufferUsages::COPY_DST`] are added to the usage, as they're needed to upload and reallocate
+	pub fn new(app: &mut HeatwaveApp, label: Option<&str>, usage: wgpu::BufferUsages, capacity: usize) -> Self {
+		let handle = app.add_buffer(wgpu::BufferDescriptor {
+			label,
+			size: Self::buffer_size(capacity),
+			usage: usage | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false
+		}).cast();
+
+		GpuVec {
+			handle,
+			values: Vec::with_capacity(capacity),
+			capacity,
+			dirty: None
+		}
+	}
+
+	///The handle of the buffer holding the list. The handle stays the same when the buffer is reallocated
+	pub fn handle(&self) -> BufferHandle<T> {
+		self.handle
+	}
+	///The number of elements in the list
+	pub fn len(&self) -> usize {
+		self.values.len()
+	}
+	///Returns true if the list has no elements
+	pub fn is_empty(&self) -> bool {
+		self.values.is_empty()
+	}
+	///The number of elements the buffer has room for before it needs reallocating
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+	///The CPU copy of the list, which includes changes that haven't been synced
+	pub fn as_slice(&self) -> &[T] {
+		&self.values
+	}
+	///Returns true if there are changes that haven't been synced
+	pub fn is_dirty(&self) -> bool {
+		self.dirty.is_some()
+	}
+
+	///Adds the value to the end of the list
+	pub fn push(&mut self, value: T) {
+		self.values.push(value);
+		self.mark_dirty(self.values.len() - 1..self.values.len());
+	}
+	///Adds the values to the end of the list
+	pub fn extend(&mut self, values: impl IntoIterator<Item = T>) {
+		let start = self.values.len();
+		self.values.extend(values);
+		self.mark_dirty(start..self.values.len());
+	}
+	///Replaces the element at `index`
+	///
+	///# Panics
+	/// Panics if `index` is out of bounds
+	pub fn set(&mut self, index: usize, value: T) {
+		self.values[index] = value;
+		self.mark_dirty(index..index + 1);
+	}
+	///Shortens the list to `len` elements. Does nothing if the list is already shorter.\
+	///The buffer keeps its size, so the space can be reused
+	pub fn truncate(&mut self, len: usize) {
+		self.values.truncate(len);
+		self.dirty = self.dirty.take()
+			.map(|dirty| dirty.start..dirty.end.min(len))
+			.filter(|dirty| !dirty.is_empty());
+	}
+	///Removes every element. The buffer keeps its size, so the space can be reused
+	pub fn clear(&mut self) {
+		self.truncate(0);
+	}
+	///Marks every element as changed, so the next sync uploads the whole list.
+	///
+	///Useful after the device is recovered, as the buffer is made again without its contents
+	pub fn invalidate(&mut self) {
+		self.mark_dirty(0..self.values.len());
+	}
+
+	///Uploads the elements that changed since the last sync, reallocating the buffer first if the list outgrew it.\
+	///The upload happens before the next work submitted to the GPU
+	///
+	///Uploads have to start and end on a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`] bytes. If `T`'s size isn't a multiple of it,
+	///the upload is widened to cover parts of the neighbouring elements, which are written from the CPU copy, and any space past the end of the list is written as zeros.\
+	///Anything a shader wrote to those neighbouring bytes is overwritten. If shaders write to the buffer, use a `T` whose size is a multiple of 4 bytes, padding it if needed
+	///
+	///# Errors
+	/// Errors if the buffer was removed from the app
+	pub fn sync(&mut self, app: &mut HeatwaveApp) -> Result<(), BufferWriteError> {
+		let Some(dirty) = self.dirty.clone() else {
+			return Ok(());
+		};
+
+		if self.values.len() > self.capacity {
+			let capacity = self.values.len().max(self.capacity * 2);
+			app.resize_buffer(self.handle.raw(), Self::buffer_size(capacity))?;
+			self.capacity = capacity;
+		}
+
+		//Writes have to be aligned, so the range is widened to the nearest aligned bytes. The buffer size is always aligned, so there's room
+		let alignment = wgpu::COPY_BUFFER_ALIGNMENT as usize;
+		let element_size = std::mem::size_of::<T>();
+		let start = dirty.start * element_size / alignment * alignment;
+		let end = (dirty.end * element_size).next_multiple_of(alignment);
+
+		let bytes: &[u8] = bytemuck::cast_slice(&self.values);
+		let mut data = bytes[start..bytes.len().min(end)].to_vec();
+		data.resize(end - start, 0);
+		app.write_buffer_slice(self.handle.cast::<u8>(), start, &data)?;
+
+		self.dirty = None;
+		Ok(())
+	}
+	///Removes the buffer from the app, returning it if it hadn't already been removed
+	pub fn remove(self, app: &mut HeatwaveApp) -> Option<wgpu::Buffer> {
+		app.remove_buffer(self.handle)
+	}
+
+	fn mark_dirty(&mut self, range: Range<usize>) {
+		if range.is_empty() {
+			return;
+		}
+		self.dirty = Some(match self.dirty.take() {
+			Some(dirty) => dirty.start.min(range.start)..dirty.end.max(range.end),
+			None => range
+		});
+	}
+	///The size of a buffer with room for `capacity` elements. Buffers are never empty, and are rounded up so any range of them can be written
+	fn buffer_size(capacity: usize) -> wgpu::BufferAddress {
+		((capacity * std::mem::size_of::<T>()) as wgpu::BufferAddress).max(1).next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT)
+	}
+}
+impl<T: bytemuck::Pod + ShaderObject> GpuVec<T> {
+	///The layout of the buffer for vertex shaders, taken from `T`
+	pub fn layout(&self) -> wgpu::VertexBufferLayout<'static> {
+		T::layout()
+	}
+}
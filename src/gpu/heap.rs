@@ -0,0 +1,176 @@
+use std::{error::Error, fmt::Display};
+
+use crate::HeatwaveApp;
+
+use super::{AllocatorStats, BindingResource, BuddyAllocator, BufferHandle, BufferWriteError, RawBufferHandle, SubAllocation};
+
+///Carves many small allocations out of a few large registered buffers, rather than making a buffer for each.\
+///Useful for thousands of small meshes, as it saves memory and lets draws share buffers.
+///
+///Backing buffers are made as they're needed, and each one is managed by a [`BuddyAllocator`].
+///
+///The backing buffers are registered without their contents, so if the device is lost they are recreated empty. Allocations stay valid, but their contents need writing again after [`WindowEvent::DeviceRecovered`](crate::WindowEvent::DeviceRecovered).
+///
+///# Usage
+///```rs
+/// let mut heap = BufferHeap::new(Some("Meshes"), wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::INDEX, 4 * 1024 * 1024);
+/// let vertices = heap.allocate_slice(&mut my_heatwave_app, &my_vertices)?;
+/// let indices = heap.allocate_slice(&mut my_heatwave_app, &my_indices)?;
+///
+/// let draw = DrawCall {
+///     vertex_buffers: &[vertices.buffer],
+///     vertex_offsets: &[vertices.offset],
+///     index_buffer: Some((indices.buffer, wgpu::IndexFormat::Uint16)),
+///     index_offset: indices.offset,
+///     ..DrawCall::new(my_pipeline, 0..my_indices.len() as u32)
+/// };
+///```
+pub struct BufferHeap {
+	label: Option<String>,
+	usage: wgpu::BufferUsages,
+	backing_size: wgpu::BufferAddress,
+	backing: Vec<(RawBufferHandle, BuddyAllocator)>
+}
+///Part of a backing buffer of a [`BufferHeap`]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HeapAllocation {
+	///The backing buffer the allocation is in
+	pub buffer: RawBufferHandle,
+	///The byte offset of the allocation in the buffer
+	pub offset: wgpu::BufferAddress,
+	///The size of the allocation in bytes
+	pub size: wgpu::BufferAddress
+}
+impl HeapAllocation {
+	///The allocation as a resource for [`HeatwaveApp::add_bind_group`]
+	pub fn binding(&self) -> BindingResource {
+		BindingResource::BufferRange {
+			buffer: self.buffer,
+			offset: self.offset,
+			size: wgpu::BufferSize::new(self.size)
+		}
+	}
+}
+///Describes why a [`BufferHeap`] couldn't allocate
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HeapError {
+	///The allocation is bigger than a backing buffer. Sizes are in bytes
+	TooLarge { size: wgpu::BufferAddress, backing_size: wgpu::BufferAddress },
+	///The contents couldn't be written to the allocation
+	Write(BufferWriteError)
+}
+impl Display for HeapError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			HeapError::TooLarge { size, backing_size } => write!(f, "An allocation of {size} bytes doesn't fit in the heap's {backing_size} byte buffers"),
+			HeapError::Write(error) => write!(f, "{error}")
+		}
+	}
+}
+impl Error for HeapError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			HeapError::Write(error) => Some(error),
+			_ => None
+		}
+	}
+}
+impl From<BufferWriteError> for HeapError {
+	fn from(value: BufferWriteError) -> Self {
+		HeapError::Write(value)
+	}
+}
+impl BufferHeap {
+	///The smallest block handed out. Keeps every offset aligned for copies, vertex buffers, and storage buffers on most devices
+	const MIN_BLOCK: wgpu::BufferAddress = 256;
+
+	///Creates an empty heap. Backing buffers of `backing_size` bytes are registered with the app as they're needed, rounded up to a power of two.
+	///
+	///[`wgpu::BufferUsages::COPY_DST`] is added to the usage, so allocations can be written to
+	pub fn new(label: Option<&str>, usage: wgpu::BufferUsages, backing_size: wgpu::BufferAddress) -> Self {
+		BufferHeap {
+			label: label.map(String::from),
+			usage: usage | wgpu::BufferUsages::COPY_DST,
+			backing_size: backing_size.max(Self::MIN_BLOCK).next_power_of_two(),
+			backing: Vec::new()
+		}
+	}
+
+	///Takes `size` bytes from the heap, starting at a multiple of `alignment`, making a new backing buffer if none of them have room.\
+	///Storage buffers bound at an offset need the device's `min_storage_buffer_offset_alignment`, while other uses are aligned well enough by default.
+	///
+	///# Errors
+	/// Errors if the allocation is bigger than a backing buffer
+	pub fn allocate(&mut self, app: &mut HeatwaveApp, size: wgpu::BufferAddress, alignment: wgpu::BufferAddress) -> Result<HeapAllocation, HeapError> {
+		let alignment = alignment.max(1).next_power_of_two();
+		if size.max(alignment) > self.backing_size {
+			return Err(HeapError::TooLarge { size, backing_size: self.backing_size });
+		}
+
+		for (buffer, allocator) in &mut self.backing {
+			if let Some(allocation) = allocator.allocate(size, alignment) {
+				return Ok(HeapAllocation { buffer: *buffer, offset: allocation.offset, size });
+			}
+		}
+
+		let buffer = app.add_buffer(wgpu::BufferDescriptor {
+			label: self.label.as_deref(),
+			size: self.backing_size,
+			usage: self.usage,
+			mapped_at_creation: false
+		}).raw();
+		let mut allocator = BuddyAllocator::new(self.backing_size, Self::MIN_BLOCK);
+		let allocation = allocator.allocate(size, alignment).expect("Expected an allocation that fits to fit in an empty backing buffer");
+		self.backing.push((buffer, allocator));
+		Ok(HeapAllocation { buffer, offset: allocation.offset, size })
+	}
+	///Takes space for the values from the heap and writes them to it. The write happens before the next work submitted to the GPU
+	///
+	///# Errors
+	/// Errors if the values don't fit in a backing buffer, or if the backing buffer was removed from the app
+	pub fn allocate_slice<T: bytemuck::Pod>(&mut self, app: &mut HeatwaveApp, values: &[T]) -> Result<HeapAllocation, HeapError> {
+		let bytes: &[u8] = bytemuck::cast_slice(values);
+		let allocation = self.allocate(app, bytes.len() as wgpu::BufferAddress, 1)?;
+
+		//Blocks are at least MIN_BLOCK bytes, so there's room to pad the write to the copy alignment
+		let mut data = bytes.to_vec();
+		data.resize(bytes.len().next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize), 0);
+		if let Err(error) = app.write_buffer_slice(BufferHandle::<u8>::new(allocation.buffer), allocation.offset as usize, &data) {
+			self.free(allocation);
+			return Err(error.into());
+		}
+		Ok(allocation)
+	}
+	///Gives the allocation's space back to the heap. Returns false if it wasn't allocated from this heap, or was already freed.
+	///
+	///Backing buffers are kept when they empty, so they can be reused
+	pub fn free(&mut self, allocation: HeapAllocation) -> bool {
+		self.backing.iter_mut()
+			.find(|(buffer, _)| *buffer == allocation.buffer)
+			.is_some_and(|(_, allocator)| allocator.free(SubAllocation { offset: allocation.offset, size: allocation.size }))
+	}
+	///Removes the backing buffers that have nothing allocated from the app
+	pub fn shrink(&mut self, app: &mut HeatwaveApp) {
+		self.backing.retain(|(buffer, allocator)| {
+			if allocator.is_empty() {
+				app.remove_buffer(*buffer);
+			}
+			!allocator.is_empty()
+		});
+	}
+	///Removes every backing buffer from the app
+	pub fn remove(self, app: &mut HeatwaveApp) {
+		for (buffer, _) in self.backing {
+			app.remove_buffer(buffer);
+		}
+	}
+
+	///The number of backing buffers made
+	pub fn backing_buffers(&self) -> usize {
+		self.backing.len()
+	}
+	///How much of the heap is used, across every backing buffer. The largest free block is the largest in any one buffer
+	pub fn stats(&self) -> AllocatorStats {
+		self.backing.iter().map(|(_, allocator)| allocator.stats()).fold(AllocatorStats::default(), AllocatorStats::combine)
+	}
+}
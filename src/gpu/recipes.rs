@@ -0,0 +1,326 @@
+//! Owned copies of the descriptors resources were created with, so the resources can be created again on a new device or with a new shader.
+//!
+//! Recipes are kept for every registered resource that Heatwave knows how to recreate. Resources created with objects Heatwave doesn't own (such as an unregistered shader module) have no recipe.
+
+use std::sync::Arc;
+
+use wgpu::util::DeviceExt;
+
+use super::{capture_validation_error, PipelineLayoutHandle, Registry, ShaderHandle};
+
+///The WGSL source of a registered shader module
+pub(crate) struct ShaderRecipe {
+	label: Option<String>,
+	source: String
+}
+impl ShaderRecipe {
+	pub(crate) fn from_descriptor(descriptor: &wgpu::ShaderModuleDescriptor) -> Option<Self> {
+		match &descriptor.source {
+			wgpu::ShaderSource::Wgsl(source) => Some(ShaderRecipe {
+				label: descriptor.label.map(String::from),
+				source: source.to_string()
+			}),
+			_ => None
+		}
+	}
+	pub(crate) fn source(&self) -> &str {
+		&self.source
+	}
+	pub(crate) fn create(&self, device: &wgpu::Device) -> Result<wgpu::ShaderModule, wgpu::Error> {
+		capture_validation_error(device, || device.create_shader_module(wgpu::ShaderModuleDescriptor {
+			label: self.label.as_deref(),
+			source: wgpu::ShaderSource::Wgsl(self.source.as_str().into())
+		}))
+	}
+}
+
+///The size and usage of a registered buffer, along with its initial contents if they were kept
+pub(crate) struct BufferRecipe {
+	label: Option<String>,
+	size: wgpu::BufferAddress,
+	usage: wgpu::BufferUsages,
+	contents: Option<Vec<u8>>
+}
+impl BufferRecipe {
+	pub(crate) fn from_descriptor(descriptor: &wgpu::BufferDescriptor) -> Self {
+		BufferRecipe {
+			label: descriptor.label.map(String::from),
+			size: descriptor.size,
+			usage: descriptor.usage,
+			contents: None //Anything written to a buffer mapped at creation can't be seen here, so it starts empty
+		}
+	}
+	///Creates a recipe for a buffer with initial contents. The contents are only copied if `keep_contents` is true
+	pub(crate) fn from_init_descriptor(descriptor: &wgpu::util::BufferInitDescriptor, keep_contents: bool) -> Self {
+		BufferRecipe {
+			label: descriptor.label.map(String::from),
+			size: descriptor.contents.len() as wgpu::BufferAddress,
+			usage: descriptor.usage,
+			contents: keep_contents.then(|| descriptor.contents.to_vec())
+		}
+	}
+	///A recipe for the same buffer with a different size. The initial contents aren't kept, as they no longer fit
+	pub(crate) fn resized(&self, size: wgpu::BufferAddress) -> Self {
+		BufferRecipe {
+			label: self.label.clone(),
+			size,
+			usage: self.usage,
+			contents: None
+		}
+	}
+	pub(crate) fn create(&self, device: &wgpu::Device) -> wgpu::Buffer {
+		match &self.contents {
+			Some(contents) => device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+				label: self.label.as_deref(),
+				contents,
+				usage: self.usage
+			}),
+			None => device.create_buffer(&wgpu::BufferDescriptor {
+				label: self.label.as_deref(),
+				size: self.size,
+				usage: self.usage,
+				mapped_at_creation: false
+			})
+		}
+	}
+}
+
+///The pipeline layout a pipeline was made with
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum LayoutRecipe {
+	///The app's pipeline layout, which is made again with the device
+	App,
+	///A layout registered with [`HeatwaveApp::add_pipeline_layout`](crate::HeatwaveApp::add_pipeline_layout)
+	Registered(PipelineLayoutHandle),
+	///A layout Heatwave doesn't own, so the pipeline can't be made again
+	Unregistered
+}
+impl LayoutRecipe {
+	fn from_descriptor(layout: Option<&wgpu::PipelineLayout>, layouts: &Registry<Arc<wgpu::PipelineLayout>>) -> Self {
+		match layout {
+			None => LayoutRecipe::App,
+			Some(layout) => layouts.iter()
+				.find(|(_, registered)| registered.global_id() == layout.global_id())
+				.map_or(LayoutRecipe::Unregistered, |(handle, _)| LayoutRecipe::Registered(handle))
+		}
+	}
+	///Returns the layout to make the pipeline with, or `None` if it isn't available
+	pub(crate) fn resolve<'a>(self, app_layout: &'a wgpu::PipelineLayout, layouts: &'a Registry<Arc<wgpu::PipelineLayout>>) -> Option<&'a wgpu::PipelineLayout> {
+		match self {
+			LayoutRecipe::App => Some(app_layout),
+			LayoutRecipe::Registered(handle) => layouts.get(handle).map(Arc::as_ref),
+			LayoutRecipe::Unregistered => None
+		}
+	}
+}
+
+///An owned [`wgpu::VertexBufferLayout`]
+struct VertexBufferRecipe {
+	array_stride: wgpu::BufferAddress,
+	step_mode: wgpu::VertexStepMode,
+	attributes: Vec<wgpu::VertexAttribute>
+}
+struct FragmentRecipe {
+	shader: ShaderHandle,
+	entry_point: String,
+	///`None` if the targets are filled in with the surface format, so they follow the surface if it changes format
+	targets: Option<Vec<Option<wgpu::ColorTargetState>>>,
+	///The blending of the filled in target
+	surface_blend: wgpu::BlendState
+}
+
+///A render pipeline that uses registered shaders
+pub(crate) struct RenderPipelineRecipe {
+	label: Option<String>,
+	layout: LayoutRecipe,
+	vertex_shader: ShaderHandle,
+	vertex_entry_point: String,
+	buffers: Vec<VertexBufferRecipe>,
+	fragment: Option<FragmentRecipe>,
+	primitive: wgpu::PrimitiveState,
+	depth_stencil: Option<wgpu::DepthStencilState>,
+	multisample: wgpu::MultisampleState,
+	multiview: Option<std::num::NonZeroU32>
+}
+impl RenderPipelineRecipe {
+	///Returns `None` if the pipeline uses a shader module that wasn't registered, as it couldn't be recreated.
+	///
+	///Takes the descriptor before substitution. Empty fragment targets are filled in with the surface format and the blending given, matching [`HeatwaveApp::add_render_pipeline`](crate::HeatwaveApp::add_render_pipeline)
+	pub(crate) fn from_descriptor(
+		descriptor: &wgpu::RenderPipelineDescriptor,
+		shaders: &Registry<Arc<wgpu::ShaderModule>>,
+		layouts: &Registry<Arc<wgpu::PipelineLayout>>,
+		surface_blend: wgpu::BlendState
+	) -> Option<Self> {
+		let fragment = match &descriptor.fragment {
+			Some(fragment) => Some(FragmentRecipe {
+				shader: find_shader(shaders, fragment.module)?,
+				entry_point: fragment.entry_point.to_string(),
+				targets: (!fragment.targets.is_empty()).then(|| fragment.targets.to_vec()),
+				surface_blend
+			}),
+			None => None
+		};
+
+		Some(RenderPipelineRecipe {
+			label: descriptor.label.map(String::from),
+			layout: LayoutRecipe::from_descriptor(descriptor.layout, layouts),
+			vertex_shader: find_shader(shaders, descriptor.vertex.module)?,
+			vertex_entry_point: descriptor.vertex.entry_point.to_string(),
+			buffers: descriptor.vertex.buffers.iter().map(|layout| VertexBufferRecipe {
+				array_stride: layout.array_stride,
+				step_mode: layout.step_mode,
+				attributes: layout.attributes.to_vec()
+			}).collect(),
+			fragment,
+			primitive: descriptor.primitive,
+			depth_stencil: descriptor.depth_stencil.clone(),
+			multisample: descriptor.multisample,
+			multiview: descriptor.multiview
+		})
+	}
+	pub(crate) fn label(&self) -> Option<&str> {
+		self.label.as_deref()
+	}
+	pub(crate) fn layout(&self) -> LayoutRecipe {
+		self.layout
+	}
+	///The shaders used by the pipeline
+	pub(crate) fn shaders(&self) -> impl Iterator<Item = ShaderHandle> + '_ {
+		std::iter::once(self.vertex_shader).chain(self.fragment.as_ref().map(|fragment| fragment.shader))
+	}
+	///Creates the pipeline with the layout given. Substituted fragment targets use the surface format given.\
+	///Pipelines using the app's layout take the sample count of the app's frames, while others keep the count they were made with
+	pub(crate) fn create(
+		&self,
+		device: &wgpu::Device,
+		layout: &wgpu::PipelineLayout,
+		shaders: &Registry<Arc<wgpu::ShaderModule>>,
+		surface_format: wgpu::TextureFormat,
+		sample_count: u32
+	) -> Result<wgpu::RenderPipeline, wgpu::Error> {
+		let buffers: Vec<wgpu::VertexBufferLayout> = self.buffers.iter().map(|buffer| wgpu::VertexBufferLayout {
+			array_stride: buffer.array_stride,
+			step_mode: buffer.step_mode,
+			attributes: &buffer.attributes
+		}).collect();
+		let surface_targets = [Some(wgpu::ColorTargetState {
+			format: surface_format,
+			blend: self.fragment.as_ref().map(|fragment| fragment.surface_blend),
+			write_mask: wgpu::ColorWrites::ALL
+		})];
+
+		let descriptor = wgpu::RenderPipelineDescriptor {
+			label: self.label.as_deref(),
+			layout: Some(layout),
+			vertex: wgpu::VertexState {
+				module: &shaders[self.vertex_shader],
+				entry_point: &self.vertex_entry_point,
+				buffers: &buffers
+			},
+			fragment: self.fragment.as_ref().map(|fragment| wgpu::FragmentState {
+				module: &shaders[fragment.shader],
+				entry_point: &fragment.entry_point,
+				targets: fragment.targets.as_deref().unwrap_or(&surface_targets)
+			}),
+			primitive: self.primitive,
+			depth_stencil: self.depth_stencil.clone(),
+			multisample: wgpu::MultisampleState {
+				count: if self.layout == LayoutRecipe::App { sample_count } else { self.multisample.count },
+				..self.multisample
+			},
+			multiview: self.multiview
+		};
+		capture_validation_error(device, || device.create_render_pipeline(&descriptor))
+	}
+}
+
+///A compute pipeline that uses a registered shader
+pub(crate) struct ComputePipelineRecipe {
+	label: Option<String>,
+	layout: LayoutRecipe,
+	shader: ShaderHandle,
+	entry_point: String
+}
+impl ComputePipelineRecipe {
+	///Returns `None` if the pipeline uses a shader module that wasn't registered, as it couldn't be recreated
+	pub(crate) fn from_descriptor(descriptor: &wgpu::ComputePipelineDescriptor, shaders: &Registry<Arc<wgpu::ShaderModule>>, layouts: &Registry<Arc<wgpu::PipelineLayout>>) -> Option<Self> {
+		Some(ComputePipelineRecipe {
+			label: descriptor.label.map(String::from),
+			layout: LayoutRecipe::from_descriptor(descriptor.layout, layouts),
+			shader: find_shader(shaders, descriptor.module)?,
+			entry_point: descriptor.entry_point.to_string()
+		})
+	}
+	pub(crate) fn label(&self) -> Option<&str> {
+		self.label.as_deref()
+	}
+	pub(crate) fn layout(&self) -> LayoutRecipe {
+		self.layout
+	}
+	///The shader used by the pipeline
+	pub(crate) fn shader(&self) -> ShaderHandle {
+		self.shader
+	}
+	pub(crate) fn create(&self, device: &wgpu::Device, layout: &wgpu::PipelineLayout, shaders: &Registry<Arc<wgpu::ShaderModule>>) -> Result<wgpu::ComputePipeline, wgpu::Error> {
+		let descriptor = wgpu::ComputePipelineDescriptor {
+			label: self.label.as_deref(),
+			layout: Some(layout),
+			module: &shaders[self.shader],
+			entry_point: &self.entry_point
+		};
+		capture_validation_error(device, || device.create_compute_pipeline(&descriptor))
+	}
+}
+
+///Finds the handle of a registered shader module
+fn find_shader(shaders: &Registry<Arc<wgpu::ShaderModule>>, module: &wgpu::ShaderModule) -> Option<ShaderHandle> {
+	shaders.iter().find(|(_, registered)| registered.global_id() == module.global_id()).map(|(handle, _)| handle)
+}
+
+///The entries of one of the app's bind group layouts
+pub(crate) struct BindGroupLayoutRecipe {
+	label: Option<String>,
+	entries: Vec<wgpu::BindGroupLayoutEntry>
+}
+impl BindGroupLayoutRecipe {
+	pub(crate) fn from_descriptor(descriptor: &wgpu::BindGroupLayoutDescriptor) -> Self {
+		BindGroupLayoutRecipe {
+			label: descriptor.label.map(String::from),
+			entries: descriptor.entries.to_vec()
+		}
+	}
+	pub(crate) fn entries(&self) -> &[wgpu::BindGroupLayoutEntry] {
+		&self.entries
+	}
+	pub(crate) fn create(&self, device: &wgpu::Device) -> wgpu::BindGroupLayout {
+		device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: self.label.as_deref(),
+			entries: &self.entries
+		})
+	}
+}
+
+///A registered sampler
+pub(crate) struct SamplerRecipe {
+	label: Option<String>,
+	///Kept without its label, so it doesn't borrow anything
+	descriptor: wgpu::SamplerDescriptor<'static>
+}
+impl SamplerRecipe {
+	pub(crate) fn from_descriptor(descriptor: &wgpu::SamplerDescriptor) -> Self {
+		SamplerRecipe {
+			label: descriptor.label.map(String::from),
+			descriptor: wgpu::SamplerDescriptor {
+				label: None,
+				..descriptor.clone()
+			}
+		}
+	}
+	pub(crate) fn create(&self, device: &wgpu::Device) -> wgpu::Sampler {
+		device.create_sampler(&wgpu::SamplerDescriptor {
+			label: self.label.as_deref(),
+			..self.descriptor.clone()
+		})
+	}
+}
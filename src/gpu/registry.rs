@@ -0,0 +1,224 @@
+use std::{collections::HashMap, fmt::Debug, hash::Hash, marker::PhantomData, ops::Index, sync::Arc};
+
+use crate::rendering::Texture;
+
+///Refers to a resource of type `T` stored in a [`Registry`].
+///
+///Handles are generational, so a handle to a removed resource never refers to a resource added later in its place.\
+///Handles to different kinds of resource are different types, so they can't be mixed up.
+pub struct Handle<T> {
+	index: u32,
+	generation: u32,
+	_marker: PhantomData<fn() -> T>
+}
+impl<T> Clone for Handle<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+impl<T> Copy for Handle<T> {}
+impl<T> PartialEq for Handle<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.index == other.index && self.generation == other.generation
+	}
+}
+impl<T> Eq for Handle<T> {}
+impl<T> Hash for Handle<T> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.index.hash(state);
+		self.generation.hash(state);
+	}
+}
+impl<T> Debug for Handle<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Handle({}v{})", self.index, self.generation)
+	}
+}
+
+///Refers to a buffer holding elements of type `T`. Defaults to bytes.
+///
+///The element type lets reads and writes be checked at compile time. Use [`BufferHandle::raw`] where the element type doesn't matter, such as in draw calls.
+pub struct BufferHandle<T = u8> {
+	raw: RawBufferHandle,
+	_marker: PhantomData<fn() -> T>
+}
+impl<T> BufferHandle<T> {
+	pub(crate) fn new(raw: RawBufferHandle) -> Self {
+		BufferHandle { raw, _marker: PhantomData }
+	}
+	///The handle without its element type
+	pub fn raw(self) -> RawBufferHandle {
+		self.raw
+	}
+	///Reinterprets the buffer as holding elements of type `U`
+	pub fn cast<U>(self) -> BufferHandle<U> {
+		BufferHandle::new(self.raw)
+	}
+}
+impl<T> Clone for BufferHandle<T> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+impl<T> Copy for BufferHandle<T> {}
+impl<T> PartialEq for BufferHandle<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.raw == other.raw
+	}
+}
+impl<T> Eq for BufferHandle<T> {}
+impl<T> Hash for BufferHandle<T> {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.raw.hash(state);
+	}
+}
+impl<T> Debug for BufferHandle<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "BufferHandle<{}>({}v{})", std::any::type_name::<T>(), self.raw.index, self.raw.generation)
+	}
+}
+impl<T> From<BufferHandle<T>> for RawBufferHandle {
+	fn from(value: BufferHandle<T>) -> Self {
+		value.raw
+	}
+}
+
+///Refers to a buffer without saying what it holds
+pub type RawBufferHandle = Handle<wgpu::Buffer>;
+///Refers to a registered render pipeline
+pub type RenderPipelineHandle = Handle<wgpu::RenderPipeline>;
+///Refers to a registered compute pipeline
+pub type ComputePipelineHandle = Handle<wgpu::ComputePipeline>;
+///Refers to a registered shader module
+pub type ShaderHandle = Handle<Arc<wgpu::ShaderModule>>;
+///Refers to a registered texture
+pub type TextureHandle = Handle<Texture>;
+///Refers to a registered sampler
+pub type SamplerHandle = Handle<wgpu::Sampler>;
+///Refers to a registered bind group
+pub type BindGroupHandle = Handle<wgpu::BindGroup>;
+///Refers to a registered pipeline layout
+pub type PipelineLayoutHandle = Handle<Arc<wgpu::PipelineLayout>>;
+
+///Stores resources of one kind, handing out a [`Handle`] for each. Resources may also be given a name to be looked up by.
+///
+///Names are usually the label of the descriptor the resource was made with. If two resources share a name, looking it up finds the newest one.
+pub struct Registry<T> {
+	slots: Vec<Slot<T>>,
+	///Indices of empty slots, reused before new slots are made
+	free: Vec<u32>,
+	///Every resource with each name, oldest first
+	names: HashMap<String, Vec<Handle<T>>>
+}
+struct Slot<T> {
+	///Increased every time the slot is emptied, so old handles stop matching
+	generation: u32,
+	value: Option<T>,
+	name: Option<String>
+}
+impl<T> Default for Registry<T> {
+	fn default() -> Self {
+		Registry {
+			slots: Vec::new(),
+			free: Vec::new(),
+			names: HashMap::new()
+		}
+	}
+}
+impl<T> Registry<T> {
+	pub(crate) fn insert(&mut self, value: T, name: Option<&str>) -> Handle<T> {
+		let index = match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.value = Some(value);
+				slot.name = name.map(String::from);
+				index
+			}
+			None => {
+				self.slots.push(Slot { generation: 0, value: Some(value), name: name.map(String::from) });
+				(self.slots.len() - 1) as u32
+			}
+		};
+
+		let handle = Handle { index, generation: self.slots[index as usize].generation, _marker: PhantomData };
+		if let Some(name) = name {
+			self.names.entry(name.to_string()).or_default().push(handle);
+		}
+		handle
+	}
+	///Swaps the resource the handle refers to for a new one, keeping the handle valid. Returns the old resource, or `None` if the handle wasn't valid
+	pub(crate) fn replace(&mut self, handle: Handle<T>, value: T) -> Option<T> {
+		let slot = self.slots.get_mut(handle.index as usize).filter(|slot| slot.generation == handle.generation)?;
+		slot.value.as_mut().map(|old| std::mem::replace(old, value))
+	}
+	///Removes the resource, returning it if the handle was still valid
+	pub(crate) fn remove(&mut self, handle: Handle<T>) -> Option<T> {
+		let slot = self.slots.get_mut(handle.index as usize).filter(|slot| slot.generation == handle.generation)?;
+		let value = slot.value.take()?;
+
+		slot.generation = slot.generation.wrapping_add(1);
+		//If another resource shares the name, lookups find the newest of those left
+		if let Some(name) = slot.name.take() {
+			if let Some(handles) = self.names.get_mut(&name) {
+				handles.retain(|&named| named != handle);
+				if handles.is_empty() {
+					self.names.remove(&name);
+				}
+			}
+		}
+		self.free.push(handle.index);
+		Some(value)
+	}
+	///Keeps only the resources `keep` returns true for
+	pub(crate) fn retain(&mut self, mut keep: impl FnMut(Handle<T>, &T) -> bool) {
+		let removed: Vec<Handle<T>> = self.iter().filter(|(handle, value)| !keep(*handle, value)).map(|(handle, _)| handle).collect();
+		for handle in removed {
+			self.remove(handle);
+		}
+	}
+	pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = (Handle<T>, &mut T)> {
+		self.slots.iter_mut().enumerate().filter_map(|(index, slot)| {
+			let generation = slot.generation;
+			slot.value.as_mut().map(|value| (Handle { index: index as u32, generation, _marker: PhantomData }, value))
+		})
+	}
+
+	///Returns the resource the handle refers to, or `None` if it was removed
+	pub fn get(&self, handle: Handle<T>) -> Option<&T> {
+		self.slots.get(handle.index as usize).filter(|slot| slot.generation == handle.generation)?.value.as_ref()
+	}
+	///Returns true if the handle refers to a resource that hasn't been removed
+	pub fn contains(&self, handle: Handle<T>) -> bool {
+		self.get(handle).is_some()
+	}
+	///Looks up a resource by name, returning its handle
+	pub fn find(&self, name: &str) -> Option<Handle<T>> {
+		self.names.get(name).and_then(|handles| handles.last()).copied()
+	}
+	///The name the resource was given, if it had one
+	pub fn name(&self, handle: Handle<T>) -> Option<&str> {
+		self.slots.get(handle.index as usize).filter(|slot| slot.generation == handle.generation && slot.value.is_some())?.name.as_deref()
+	}
+	///Every resource stored, along with its handle
+	pub fn iter(&self) -> impl Iterator<Item = (Handle<T>, &T)> {
+		self.slots.iter().enumerate().filter_map(|(index, slot)| {
+			slot.value.as_ref().map(|value| (Handle { index: index as u32, generation: slot.generation, _marker: PhantomData }, value))
+		})
+	}
+	///The number of resources stored
+	pub fn len(&self) -> usize {
+		self.slots.len() - self.free.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+}
+impl<T> Index<Handle<T>> for Registry<T> {
+	type Output = T;
+
+	///# Panics
+	/// Panics if the resource was removed
+	fn index(&self, handle: Handle<T>) -> &Self::Output {
+		self.get(handle).expect("Expected a handle to a resource that hasn't been removed")
+	}
+}
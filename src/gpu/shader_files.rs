@@ -0,0 +1,119 @@
+use std::{
+	collections::HashMap,
+	error::Error,
+	fmt::Display,
+	io,
+	path::{Path, PathBuf},
+	sync::Arc,
+	time::{Duration, Instant, SystemTime}
+};
+
+use super::{GpuError, ShaderHandle};
+
+///Why a shader file couldn't be loaded or reloaded. Cloneable, so it can be sent to the presenter
+#[derive(Clone, PartialEq, Debug)]
+pub struct ShaderFileError {
+	///The file the shader was loaded from
+	pub path: PathBuf,
+	///What went wrong, along with the error that caused it
+	pub kind: ShaderFileErrorKind
+}
+///What went wrong while loading or reloading a shader file
+#[derive(Clone, Debug)]
+pub enum ShaderFileErrorKind {
+	///The file couldn't be read. Shared, as io errors can't be cloned
+	Read(Arc<io::Error>),
+	///The shader didn't compile. Contains the error wgpu reported
+	Compile(GpuError),
+	///The shader compiled, but a pipeline using it couldn't be made with it
+	Pipeline {
+		///The label of the pipeline, if it had one
+		label: Option<String>,
+		///The error wgpu reported for the pipeline
+		error: GpuError
+	}
+}
+impl PartialEq for ShaderFileErrorKind {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			//io errors can't be compared, so reads are equal if they failed the same way
+			(ShaderFileErrorKind::Read(error), ShaderFileErrorKind::Read(other)) => error.kind() == other.kind() && error.to_string() == other.to_string(),
+			(ShaderFileErrorKind::Compile(error), ShaderFileErrorKind::Compile(other)) => error == other,
+			(ShaderFileErrorKind::Pipeline { label, error }, ShaderFileErrorKind::Pipeline { label: other_label, error: other }) => label == other_label && error == other,
+			_ => false
+		}
+	}
+}
+impl Display for ShaderFileError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let path = self.path.display();
+		match &self.kind {
+			ShaderFileErrorKind::Read(error) => write!(f, "Failed to read shader file \"{path}\": {error}"),
+			ShaderFileErrorKind::Compile(error) => write!(f, "Failed to compile shader file \"{path}\": {}", error.message),
+			ShaderFileErrorKind::Pipeline { label: Some(label), error } => write!(f, "Pipeline \"{label}\" rejected shader file \"{path}\": {}", error.message),
+			ShaderFileErrorKind::Pipeline { label: None, error } => write!(f, "An unlabelled pipeline rejected shader file \"{path}\": {}", error.message)
+		}
+	}
+}
+impl Error for ShaderFileError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match &self.kind {
+			ShaderFileErrorKind::Read(error) => Some(error.as_ref()),
+			ShaderFileErrorKind::Compile(error) | ShaderFileErrorKind::Pipeline { error, .. } => Some(error)
+		}
+	}
+}
+
+///A shader file being watched, along with when it was last seen to change
+struct WatchedFile {
+	path: PathBuf,
+	modified: Option<SystemTime>
+}
+
+///Tracks the files registered shaders were loaded from, so they can be reloaded when they change
+#[derive(Default)]
+pub(crate) struct ShaderWatcher {
+	files: HashMap<ShaderHandle, WatchedFile>,
+	last_poll: Option<Instant>
+}
+impl ShaderWatcher {
+	///How often the default event handler checks the files for changes
+	const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+	pub(crate) fn watch(&mut self, handle: ShaderHandle, path: PathBuf) {
+		let modified = modified_time(&path);
+		self.files.insert(handle, WatchedFile { path, modified });
+	}
+	pub(crate) fn unwatch(&mut self, handle: ShaderHandle) {
+		self.files.remove(&handle);
+	}
+	pub(crate) fn path(&self, handle: ShaderHandle) -> Option<&Path> {
+		self.files.get(&handle).map(|file| file.path.as_path())
+	}
+	///Returns true if the poll interval has passed since this last returned true
+	pub(crate) fn is_due(&mut self) -> bool {
+		let now = Instant::now();
+		if self.last_poll.is_some_and(|last| now.duration_since(last) < Self::POLL_INTERVAL) {
+			return false;
+		}
+		self.last_poll = Some(now);
+		true
+	}
+	///Returns every shader whose file has been modified since this was last called.\
+	///Files that can't be checked right now, such as while an editor is replacing them, are skipped until they can be
+	pub(crate) fn changed(&mut self) -> Vec<(ShaderHandle, PathBuf)> {
+		let mut changed = Vec::new();
+		for (handle, file) in &mut self.files {
+			let modified = modified_time(&file.path);
+			if modified.is_some() && modified != file.modified {
+				file.modified = modified;
+				changed.push((*handle, file.path.clone()));
+			}
+		}
+		changed
+	}
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,250 @@
+use std::{
+	error::Error,
+	fmt::Display,
+	num::NonZeroU64,
+	sync::{atomic::{AtomicBool, Ordering}, Arc, Mutex, MutexGuard}
+};
+
+///How the app's uniform ring is laid out. See [`HeatwaveConfig::uniform_ring`](crate::HeatwaveConfig::uniform_ring)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UniformRingConfig {
+	///The size in bytes of each buffer the ring allocates from. More buffers are made when a frame fills the ones available.\
+	///This must be a multiple of the device's `min_uniform_buffer_offset_alignment`, and no more than its `max_uniform_buffer_binding_size`.
+	///
+	///Defaults to 64KiB
+	pub block_size: wgpu::BufferAddress,
+	///The size in bytes of the uniform bound for each draw. This must be at least the size of the largest uniform allocated, a multiple of the device's `min_uniform_buffer_offset_alignment`, and no more than its `max_uniform_buffer_binding_size`.
+	///
+	///Defaults to 256 bytes
+	pub binding_size: wgpu::BufferAddress,
+	///Which shader stages can see the uniform.
+	///
+	///Defaults to the vertex and fragment stages
+	pub visibility: wgpu::ShaderStages
+}
+impl Default for UniformRingConfig {
+	fn default() -> Self {
+		UniformRingConfig {
+			block_size: 64 * 1024,
+			binding_size: 256,
+			visibility: wgpu::ShaderStages::VERTEX_FRAGMENT
+		}
+	}
+}
+
+///Where a uniform was allocated in the app's uniform ring. Set it on [`DrawCall::uniforms`](crate::rendering::DrawCall::uniforms) to bind it for a draw.
+///
+///Allocations are only valid for the frame they were made in, as their space is reused once the GPU has finished with the frame.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct UniformAllocation {
+	block: usize,
+	offset: u32
+}
+impl UniformAllocation {
+	///The dynamic offset of the uniform in its buffer
+	pub fn offset(&self) -> u32 {
+		self.offset
+	}
+}
+
+///Describes why the uniform ring couldn't be created, or a uniform couldn't be allocated
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UniformRingError {
+	///[`HeatwaveConfig::uniform_ring`](crate::HeatwaveConfig::uniform_ring) isn't set, so there's no ring to allocate from
+	Disabled,
+	///The uniform is bigger than the ring's binding size. Sizes are in bytes
+	TooLarge { size: wgpu::BufferAddress, binding_size: wgpu::BufferAddress },
+	///The ring's binding or block size is bigger than the device's `max_uniform_buffer_binding_size`. Sizes are in bytes
+	ExceedsDeviceLimit { size: wgpu::BufferAddress, limit: wgpu::BufferAddress },
+	///The ring's binding or block size isn't a multiple of the device's `min_uniform_buffer_offset_alignment`. Sizes are in bytes
+	Unaligned { size: wgpu::BufferAddress, alignment: wgpu::BufferAddress }
+}
+impl Display for UniformRingError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			UniformRingError::Disabled => write!(f, "The app has no uniform ring to allocate from"),
+			UniformRingError::TooLarge { size, binding_size } => write!(f, "The uniform is {size} bytes, but the uniform ring binds {binding_size} bytes"),
+			UniformRingError::ExceedsDeviceLimit { size, limit } => write!(f, "The uniform ring uses a size of {size} bytes, but the device can only bind {limit} bytes of a uniform buffer"),
+			UniformRingError::Unaligned { size, alignment } => write!(f, "The uniform ring uses a size of {size} bytes, which isn't a multiple of the device's {alignment} byte uniform offset alignment")
+		}
+	}
+}
+impl Error for UniformRingError {}
+
+///Hands out aligned space for uniforms from a few large buffers, which is reused once the GPU has finished with the frame it was used in.
+///
+///Each buffer is bound with a dynamic offset, so one bind group per buffer serves every uniform in it.
+pub(crate) struct UniformRing {
+	config: UniformRingConfig,
+	///The group index the ring is bound to, which comes after the config's bind groups
+	group: u32,
+	///The alignment of every allocation, from the device's `min_uniform_buffer_offset_alignment`
+	alignment: wgpu::BufferAddress,
+	layout: wgpu::BindGroupLayout,
+	//Allocations are made while drawing, when the app is only borrowed immutably
+	state: Mutex<RingState>
+}
+pub(crate) struct RingState {
+	blocks: Vec<Block>,
+	///Blocks the GPU has finished with
+	free: Vec<usize>,
+	///The block being allocated from, and the first byte after the last allocation in it
+	current: Option<(usize, wgpu::BufferAddress)>,
+	///Blocks allocated from since the last frame was finished
+	frame: Vec<usize>,
+	///Blocks used by submitted frames, along with a flag set once the GPU has finished the frame
+	in_flight: Vec<(Arc<AtomicBool>, Vec<usize>)>
+}
+struct Block {
+	buffer: wgpu::Buffer,
+	bind_group: wgpu::BindGroup
+}
+impl UniformRing {
+	///Errors if the binding or block size doesn't fit the device's uniform buffer limits
+	pub(crate) fn new(device: &wgpu::Device, config: UniformRingConfig, group: u32) -> Result<Self, UniformRingError> {
+		let limits = device.limits();
+		let limit = limits.max_uniform_buffer_binding_size as wgpu::BufferAddress;
+		let alignment = limits.min_uniform_buffer_offset_alignment as wgpu::BufferAddress;
+		for size in [config.binding_size, config.block_size] {
+			if size > limit {
+				return Err(UniformRingError::ExceedsDeviceLimit { size, limit });
+			}
+			if size % alignment != 0 {
+				return Err(UniformRingError::Unaligned { size, alignment });
+			}
+		}
+
+		Ok(UniformRing::create(device, config, group))
+	}
+	fn create(device: &wgpu::Device, config: UniformRingConfig, group: u32) -> Self {
+		let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label: Some("Heatwave Uniform Ring Layout"),
+			entries: &[wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: config.visibility,
+				ty: wgpu::BindingType::Buffer {
+					ty: wgpu::BufferBindingType::Uniform,
+					has_dynamic_offset: true,
+					min_binding_size: NonZeroU64::new(config.binding_size)
+				},
+				count: None
+			}]
+		});
+
+		UniformRing {
+			config,
+			group,
+			alignment: (device.limits().min_uniform_buffer_offset_alignment as wgpu::BufferAddress).max(wgpu::COPY_BUFFER_ALIGNMENT),
+			layout,
+			state: Mutex::new(RingState {
+				blocks: Vec::new(),
+				free: Vec::new(),
+				current: None,
+				frame: Vec::new(),
+				in_flight: Vec::new()
+			})
+		}
+	}
+	///Makes the ring again for a new device, dropping every block.\
+	///The new device is requested with the same limits, so the config doesn't need validating again
+	pub(crate) fn recreate(&self, device: &wgpu::Device) -> Self {
+		UniformRing::create(device, self.config, self.group)
+	}
+	pub(crate) fn layout(&self) -> &wgpu::BindGroupLayout {
+		&self.layout
+	}
+	pub(crate) fn group(&self) -> u32 {
+		self.group
+	}
+	pub(crate) fn lock(&self) -> MutexGuard<'_, RingState> {
+		self.state.lock().expect("Uniform ring was poisoned")
+	}
+
+	///Copies the bytes into the next free space in the ring
+	pub(crate) fn allocate(&self, device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8]) -> Result<UniformAllocation, UniformRingError> {
+		let size = bytes.len() as wgpu::BufferAddress;
+		if size > self.config.binding_size {
+			return Err(UniformRingError::TooLarge { size, binding_size: self.config.binding_size });
+		}
+
+		let mut state = self.lock();
+		state.reclaim();
+
+		//Every allocation is bound with the full binding size, so that much has to fit after its offset
+		let (block, offset) = match state.current {
+			Some((block, end)) if end.next_multiple_of(self.alignment) + self.config.binding_size <= self.config.block_size => (block, end.next_multiple_of(self.alignment)),
+			_ => {
+				let block = match state.free.pop() {
+					Some(block) => block,
+					None => {
+						state.blocks.push(self.create_block(device));
+						state.blocks.len() - 1
+					}
+				};
+				state.frame.push(block);
+				(block, 0)
+			}
+		};
+		state.current = Some((block, offset + size));
+
+		let mut data = bytes.to_vec();
+		data.resize(bytes.len().next_multiple_of(wgpu::COPY_BUFFER_ALIGNMENT as usize), 0);
+		queue.write_buffer(&state.blocks[block].buffer, offset, &data);
+
+		Ok(UniformAllocation { block, offset: offset as u32 })
+	}
+	///Marks the blocks allocated from since the last call as belonging to submitted work, so they're reused once the GPU has finished with it
+	pub(crate) fn finish_frame(&self, device: &wgpu::Device, queue: &wgpu::Queue) {
+		let mut state = self.lock();
+		state.current = None;
+		if !state.frame.is_empty() {
+			let finished = Arc::new(AtomicBool::new(false));
+			let flag = finished.clone();
+			queue.on_submitted_work_done(move || flag.store(true, Ordering::Release));
+
+			let frame = std::mem::take(&mut state.frame);
+			state.in_flight.push((finished, frame));
+		}
+		drop(state);
+		device.poll(wgpu::Maintain::Poll); //Runs the callbacks of frames that have already finished
+	}
+
+	fn create_block(&self, device: &wgpu::Device) -> Block {
+		let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+			label: Some("Heatwave Uniform Ring Block"),
+			size: self.config.block_size,
+			usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+			mapped_at_creation: false
+		});
+		let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+			label: Some("Heatwave Uniform Ring Bind Group"),
+			layout: &self.layout,
+			entries: &[wgpu::BindGroupEntry {
+				binding: 0,
+				resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+					buffer: &buffer,
+					offset: 0,
+					size: NonZeroU64::new(self.config.binding_size)
+				})
+			}]
+		});
+		Block { buffer, bind_group }
+	}
+}
+impl RingState {
+	///The bind group to bind the allocation with
+	pub(crate) fn bind_group(&self, allocation: UniformAllocation) -> Option<&wgpu::BindGroup> {
+		self.blocks.get(allocation.block).map(|block| &block.bind_group)
+	}
+	///Frees the blocks of every frame the GPU has finished
+	fn reclaim(&mut self) {
+		let free = &mut self.free;
+		self.in_flight.retain(|(finished, blocks)| {
+			let done = finished.load(Ordering::Acquire);
+			if done {
+				free.extend(blocks);
+			}
+			!done
+		});
+	}
+}
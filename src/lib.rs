@@ -6,8 +6,10 @@
 //! Though this project uses wgpu in its backend, it doesn't currently fully support wasm.
 
 use std::{
-    collections::HashMap,
-    path::PathBuf,
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
     sync::{
         mpsc::{channel, Receiver, Sender},
         Arc
@@ -16,12 +18,18 @@ use std::{
     time::Duration,
 };
 
-use gpu::{GpuConnection, GpuConnectionError};
-use log::{error, warn};
-use rendering::{AnalogAxisEventArgs, KeyPressEventArgs, MousePressEventArgs, MouseScrollEventArgs, Presenter, RenderHelper};
+use gpu::{
+    AdapterSelector, BindGroupError, BindGroupHandle, BindGroupLayoutRecipe, BindGroupRecipe, BindingResource, BufferHandle, BufferRecipe, BufferWriteError, ComputeDispatch, ComputeError,
+    ComputePipelineHandle, ComputePipelineRecipe, ComputeReadback, FrameError, GpuConnection, GpuConnectionError, GpuError, GpuEvent, HeapError, LayoutRecipe, LimitsPreset, PipelineLayoutHandle, PresentSettings,
+    RawBufferHandle, Registry, RenderPipelineHandle, RenderPipelineRecipe, SamplerHandle, SamplerRecipe, ShaderFileError, ShaderFileErrorKind, ShaderHandle, ShaderRecipe, ShaderWatcher, TextureHandle, UniformAllocation,
+    UniformRing, UniformRingConfig, UniformRingError,
+};
+use log::{error, info, warn};
+use rendering::{AnalogAxisEventArgs, AtlasError, KeyPressEventArgs, MipmapGenerator, MousePressEventArgs, MouseScrollEventArgs, PreprocessError, Presenter, ReflectionError, RenderError, RenderHelper, ShaderReflection, Texture, TextureError, TextureOptions, VertexLayoutError};
 use wgpu::{
     util::{BufferInitDescriptor, DeviceExt},
-    BindGroupLayoutDescriptor, BufferDescriptor, ComputePipelineDescriptor, Features, PipelineLayout, PushConstantRange, RenderPipelineDescriptor,
+    BindGroupLayoutDescriptor, BufferDescriptor, ComputePipelineDescriptor, Features, PipelineLayout, PushConstantRange, RenderPipelineDescriptor, SamplerDescriptor,
+    ShaderModuleDescriptor,
 };
 use winit::{
     dpi::{PhysicalPosition, PhysicalSize},
@@ -37,23 +45,47 @@ pub mod gpu;
 pub mod rendering;
 
 ///Represents an app in Heatwave, with references to the presenter, window and GPU bindings/
-
 pub struct HeatwaveApp<'a> {
     connection: GpuConnection<'a>,
 
-    buffers: HashMap<usize, wgpu::Buffer>,
-    next_buffer_id: usize,
+    buffers: Registry<wgpu::Buffer>,
+    render_pipelines: Registry<wgpu::RenderPipeline>,
+    compute_pipelines: Registry<wgpu::ComputePipeline>,
+    ///The render pipelines made with the app's pipeline layout, which the app's bound groups are set for
+    app_layout_pipelines: HashSet<RenderPipelineHandle>,
+    shaders: Registry<Arc<wgpu::ShaderModule>>,
+    textures: Registry<Texture>,
+    samplers: Registry<wgpu::Sampler>,
+    ///Custom pipeline layouts, so pipelines using them can be made again when their shaders are reloaded
+    pipeline_layouts: Registry<Arc<wgpu::PipelineLayout>>,
+
+    ///The layouts from the config, indexed by group
+    bind_group_layouts: Vec<(wgpu::BindGroupLayout, BindGroupLayoutRecipe)>,
+    bind_groups: Registry<wgpu::BindGroup>,
+    ///The bind group set at each group index when drawing, indexed by group
+    bound_groups: Vec<Option<BindGroupHandle>>,
+    ///Hands out space for per-frame uniforms, bound at the group after the config's bind groups
+    uniform_ring: Option<UniformRing>,
+    ///Renders the mips of loaded textures, keeping its pipelines between loads
+    mipmaps: MipmapGenerator,
 
-    render_pipelines: HashMap<usize, wgpu::RenderPipeline>,
-    compute_pipelines: HashMap<usize, wgpu::ComputePipeline>,
-    next_render_id: usize,
-    next_compute_id: usize,
+    //Kept so resources can be created again if the device is lost
+    shader_recipes: HashMap<ShaderHandle, ShaderRecipe>,
+    ///The files shaders were loaded from, checked for changes while running
+    shader_watcher: ShaderWatcher,
+    buffer_recipes: HashMap<RawBufferHandle, BufferRecipe>,
+    render_recipes: HashMap<RenderPipelineHandle, RenderPipelineRecipe>,
+    compute_recipes: HashMap<ComputePipelineHandle, ComputePipelineRecipe>,
+    sampler_recipes: HashMap<SamplerHandle, SamplerRecipe>,
+    bind_group_recipes: HashMap<BindGroupHandle, BindGroupRecipe>,
+    push_constants: Vec<PushConstantRange>,
+    recover_lost_device: bool,
 
     skybox: wgpu::Color,
     pipeline_layout: PipelineLayout,
 
     event_loop: Option<EventLoop<()>>,
-    window: Arc<Window>,
+    window: Option<Arc<Window>>,
 }
 impl<'a> HeatwaveApp<'a> {
     #[cfg(target_arch = "wasm32")]
@@ -72,7 +104,15 @@ impl<'a> HeatwaveApp<'a> {
         env_logger::init();
     }
 
+    ///Creates a new app, opening a window described by the config.
+    ///
+    ///If [`HeatwaveConfig::headless`] is set, no window is opened and the app is created with [`HeatwaveApp::new_headless`] instead.
     pub async fn new<'b>(config: HeatwaveConfig<'b>) -> Result<Self, HeatwaveInitialiseError> {
+        if config.headless {
+            return HeatwaveApp::new_headless(config).await;
+        }
+        config.validate().check_window_issues()?;
+
         let mut window = WindowBuilder::new()
             .with_title(&config.name)
             .with_inner_size(config.default_size)
@@ -103,6 +143,8 @@ impl<'a> HeatwaveApp<'a> {
     ///
     ///**Window-related configuration in HeatwaveConfig is ignored**
     pub async fn new_with_window<'b>(builder: WindowBuilder, config: HeatwaveConfig<'b>) -> Result<Self, HeatwaveInitialiseError> {
+        config.validate().check_graphics_issues()?;
+
         let event_loop = EventLoop::new()?;
         let window = builder.build(&event_loop)?;
         let window_ref = Arc::new(window);
@@ -111,69 +153,332 @@ impl<'a> HeatwaveApp<'a> {
 
         let connection = connection_future.await?;
 
-        let pipeline_layout = connection.device().create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-            label: Some("Heatwave Render Pipeline Layout"),
-            bind_group_layouts: &[], //todo: Bind groups https://sotrh.github.io/learn-wgpu/beginner/tutorial5-textures/#the-bindgroup
-            push_constant_ranges: config.push_constants,
-        });
+        HeatwaveApp::from_connection(connection, Some(window_ref), Some(event_loop), &config)
+    }
+    ///Creates a new app without a window or event loop, drawing frames to an offscreen texture instead.\
+    ///Useful for tests, CI and servers, as it will fall back to a software adapter if no GPU is available.
+    ///
+    ///The offscreen texture uses the size given by [`HeatwaveConfig::default_size`], and can be read back with [`GpuConnection::read_offscreen_pixels`].
+    ///
+    ///**Window-related configuration in HeatwaveConfig is ignored**\
+    ///Headless apps have no event loop, so can't be turned into a [`HeatwaveRunner`]. Create a [`RenderHelper`] directly to draw a frame.
+    pub async fn new_headless<'b>(config: HeatwaveConfig<'b>) -> Result<Self, HeatwaveInitialiseError> {
+        config.validate().check_graphics_issues()?;
+
+        let connection = GpuConnection::new_headless(&config).await?;
+
+        HeatwaveApp::from_connection(connection, None, None, &config)
+    }
+    fn from_connection(connection: GpuConnection<'a>, window: Option<Arc<Window>>, event_loop: Option<EventLoop<()>>, config: &HeatwaveConfig) -> Result<Self, HeatwaveInitialiseError> {
+        let bind_group_layouts: Vec<_> = config.bind_groups.iter()
+            .map(|descriptor| {
+                let recipe = BindGroupLayoutRecipe::from_descriptor(descriptor);
+                (recipe.create(connection.device()), recipe)
+            })
+            .collect();
+        let uniform_ring = config.uniform_ring.map(|ring_config| UniformRing::new(connection.device(), ring_config, bind_group_layouts.len() as u32)).transpose()?;
+
         Ok(HeatwaveApp {
-            window: window_ref,
-            event_loop: Some(event_loop),
-            buffers: HashMap::new(),
-            next_buffer_id: 0,
-            render_pipelines: HashMap::new(),
-            compute_pipelines: HashMap::new(),
-            next_compute_id: 0,
-            next_render_id: 0,
+            window,
+            event_loop,
+            buffers: Registry::default(),
+            render_pipelines: Registry::default(),
+            compute_pipelines: Registry::default(),
+            app_layout_pipelines: HashSet::new(),
+            shaders: Registry::default(),
+            textures: Registry::default(),
+            samplers: Registry::default(),
+            pipeline_layouts: Registry::default(),
+            bound_groups: vec![None; bind_group_layouts.len()],
+            bind_groups: Registry::default(),
+            shader_recipes: HashMap::new(),
+            shader_watcher: ShaderWatcher::default(),
+            buffer_recipes: HashMap::new(),
+            render_recipes: HashMap::new(),
+            compute_recipes: HashMap::new(),
+            sampler_recipes: HashMap::new(),
+            bind_group_recipes: HashMap::new(),
+            push_constants: config.push_constants.to_vec(),
+            recover_lost_device: config.recover_lost_device,
+            pipeline_layout: Self::create_pipeline_layout(connection.device(), &bind_group_layouts, uniform_ring.as_ref(), config.push_constants),
+            bind_group_layouts,
+            uniform_ring,
+            mipmaps: MipmapGenerator::default(),
             connection,
-            pipeline_layout,
             skybox: config.skybox,
         })
     }
+    fn create_pipeline_layout(
+        device: &wgpu::Device,
+        bind_group_layouts: &[(wgpu::BindGroupLayout, BindGroupLayoutRecipe)],
+        uniform_ring: Option<&UniformRing>,
+        push_constants: &[PushConstantRange],
+    ) -> PipelineLayout {
+        let bind_group_layouts: Vec<&wgpu::BindGroupLayout> = bind_group_layouts.iter().map(|(layout, _)| layout).chain(uniform_ring.map(UniformRing::layout)).collect();
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Heatwave Render Pipeline Layout"),
+            bind_group_layouts: &bind_group_layouts,
+            push_constant_ranges: push_constants,
+        })
+    }
+
+    ///Adds a new shader module to the heatwave window using the descriptor provided.
+    ///
+    ///Returns a handle to the shader. Use [`HeatwaveApp::shader`] to get the module for pipeline descriptors.\
+    ///Pipelines made with registered shaders and the app's pipeline layout can be recreated if the device is lost.
+    ///
+    ///The shader can be looked up by its label with [`HeatwaveApp::shaders`]
+    ///
+    ///# Errors
+    /// Errors if wgpu rejects the shader, such as when the WGSL doesn't compile
+    pub fn add_shader(&mut self, descriptor: ShaderModuleDescriptor) -> Result<ShaderHandle, ShaderError> {
+        let label = descriptor.label.map(String::from);
+        let recipe = ShaderRecipe::from_descriptor(&descriptor);
+
+        let device = self.connection.device();
+        let module = gpu::capture_validation_error(device, || device.create_shader_module(descriptor)).map_err(|source| ShaderError { label: label.clone(), source })?;
+
+        let handle = self.shaders.insert(Arc::new(module), label.as_deref());
+        if let Some(recipe) = recipe {
+            self.shader_recipes.insert(handle, recipe);
+        }
+        Ok(handle)
+    }
+    ///Loads a WGSL shader from a file and watches the file for changes. The shader is labelled with the file's name.
+    ///
+    ///When the file changes, [`HeatwaveApp::reload_shaders`] recreates the shader and every pipeline using it, which the default event handler does while running.
+    ///
+    ///# Errors
+    /// Errors if the file can't be read, or if the WGSL doesn't compile
+    pub fn load_shader(&mut self, path: impl AsRef<Path>) -> Result<ShaderHandle, ShaderFileError> {
+        let path = path.as_ref().to_path_buf();
+        let source = std::fs::read_to_string(&path).map_err(|error| ShaderFileError { path: path.clone(), kind: ShaderFileErrorKind::Read(Arc::new(error)) })?;
+        let label = path.file_name().map(|name| name.to_string_lossy().into_owned());
+
+        let handle = self
+            .add_shader(ShaderModuleDescriptor {
+                label: label.as_deref(),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            })
+            .map_err(|error| ShaderFileError { path: path.clone(), kind: ShaderFileErrorKind::Compile(error.source.into()) })?;
+        self.shader_watcher.watch(handle, path);
+        Ok(handle)
+    }
+    ///Reloads every shader loaded with [`HeatwaveApp::load_shader`] whose file has changed since it was last loaded, returning the handle or error for each.
+    ///
+    ///Each render and compute pipeline made with the shader is recreated with the new module, as long as its layout is the app's or was registered with [`HeatwaveApp::add_pipeline_layout`].\
+    ///Pipelines using an unregistered layout or a removed shader keep the old module, and an error is logged for each.\
+    ///If the shader doesn't compile, or any of its pipelines can't be recreated, the old shader and pipelines are kept and the error is returned. The file is tried again once it changes again
+    pub fn reload_shaders(&mut self) -> Vec<Result<ShaderHandle, ShaderFileError>> {
+        self.shader_watcher
+            .changed()
+            .into_iter()
+            .filter_map(|(handle, path)| {
+                if !self.shaders.contains(handle) {
+                    self.shader_watcher.unwatch(handle);
+                    return None;
+                }
+                let result = self.reload_shader(handle, &path);
+                match &result {
+                    Ok(_) => info!("Reloaded shader \"{}\"", path.display()),
+                    Err(error) => error!("{error}"),
+                }
+                Some(result)
+            })
+            .collect()
+    }
+    fn reload_shader(&mut self, handle: ShaderHandle, path: &Path) -> Result<ShaderHandle, ShaderFileError> {
+        let file_error = |kind| ShaderFileError { path: path.to_path_buf(), kind };
+        let source = std::fs::read_to_string(path).map_err(|error| file_error(ShaderFileErrorKind::Read(Arc::new(error))))?;
+        let descriptor = ShaderModuleDescriptor {
+            label: self.shaders.name(handle),
+            source: wgpu::ShaderSource::Wgsl(source.as_str().into()),
+        };
+        let recipe = ShaderRecipe::from_descriptor(&descriptor);
+
+        let device = self.connection.device();
+        let module = gpu::capture_validation_error(device, || device.create_shader_module(descriptor)).map_err(|error| file_error(ShaderFileErrorKind::Compile(error.into())))?;
+        let old_module = self.shaders.replace(handle, Arc::new(module)).expect("Reloaded shader was checked to be registered");
+
+        //Every pipeline is made before any are replaced, so nothing changes if one of them fails
+        let surface_format = self.connection.surface_config().format;
+        let sample_count = self.connection.sample_count();
+        let render_pipelines: Result<Vec<_>, PipelineError> = self
+            .render_recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.shaders().any(|shader| shader == handle))
+            .filter_map(|(pipeline, recipe)| {
+                let layout = recipe.layout().resolve(&self.pipeline_layout, &self.pipeline_layouts).filter(|_| recipe.shaders().all(|shader| self.shaders.contains(shader)));
+                let Some(layout) = layout else {
+                    error!("Render pipeline {pipeline:?} uses an unregistered pipeline layout or a removed shader, so it keeps the old shader \"{}\"", path.display());
+                    return None;
+                };
+                Some(recipe
+                    .create(device, layout, &self.shaders, surface_format, sample_count)
+                    .map(|recreated| (*pipeline, recreated))
+                    .map_err(|error| PipelineError::new(recipe.label(), error)))
+            })
+            .collect();
+        let compute_pipelines: Result<Vec<_>, PipelineError> = self
+            .compute_recipes
+            .iter()
+            .filter(|(_, recipe)| recipe.shader() == handle)
+            .filter_map(|(pipeline, recipe)| {
+                let Some(layout) = recipe.layout().resolve(&self.pipeline_layout, &self.pipeline_layouts) else {
+                    error!("Compute pipeline {pipeline:?} uses an unregistered pipeline layout, so it keeps the old shader \"{}\"", path.display());
+                    return None;
+                };
+                Some(recipe
+                    .create(device, layout, &self.shaders)
+                    .map(|recreated| (*pipeline, recreated))
+                    .map_err(|error| PipelineError::new(recipe.label(), error)))
+            })
+            .collect();
+
+        let (render_pipelines, compute_pipelines) = match render_pipelines.and_then(|render| compute_pipelines.map(|compute| (render, compute))) {
+            Ok(pipelines) => pipelines,
+            Err(error) => {
+                self.shaders.replace(handle, old_module);
+                return Err(file_error(ShaderFileErrorKind::Pipeline { label: error.label, error: error.source.into() }));
+            }
+        };
+        for (pipeline, recreated) in render_pipelines {
+            self.render_pipelines.replace(pipeline, recreated);
+        }
+        for (pipeline, recreated) in compute_pipelines {
+            self.compute_pipelines.replace(pipeline, recreated);
+        }
+        if let Some(recipe) = recipe {
+            self.shader_recipes.insert(handle, recipe);
+        }
+        Ok(handle)
+    }
 
     ///Adds a new buffer to the heatwave window using the descriptor provided.
     ///
-    ///Returns the ID of the buffer, for later access
+    ///Returns a handle to the buffer, for later access. The buffer can be looked up by its label with [`HeatwaveApp::buffers`]
+    ///
+    ///The handle treats the buffer as bytes. Use [`BufferHandle::cast`] to treat it as holding another type
     ///# Substitution
     /// Has no substitution behaviour.
-    pub fn add_buffer<'b>(&mut self, descriptor: impl Into<BufferDescriptor<'b>>) -> usize {
+    pub fn add_buffer<'b>(&mut self, descriptor: impl Into<BufferDescriptor<'b>>) -> BufferHandle {
         let desc = descriptor.into();
 
         let buffer = self.connection.device().create_buffer(&desc);
 
-        self.buffers.insert(self.next_buffer_id, buffer);
-        self.next_buffer_id += 1;
-        self.next_buffer_id - 1
+        let handle = self.buffers.insert(buffer, desc.label);
+        self.buffer_recipes.insert(handle, BufferRecipe::from_descriptor(&desc));
+        BufferHandle::new(handle)
     }
     ///Adds a new buffer to the heatwave window using the descriptor provided.
     ///Specifically for buffers that have starting values
     ///
-    ///Returns the ID of the buffer, for later access
+    ///Returns a handle to the buffer, for later access. The buffer can be looked up by its label with [`HeatwaveApp::buffers`]
+    ///
+    ///The handle treats the buffer as bytes. Use [`HeatwaveApp::add_buffer_from_slice`] to keep the type of the starting values
     ///
     /// # Substitution
     /// Has no substitution behaviour.
-    pub fn add_buffer_with_defaults<'b>(&mut self, descriptor: impl Into<BufferInitDescriptor<'b>>) -> usize {
+    pub fn add_buffer_with_defaults<'b>(&mut self, descriptor: impl Into<BufferInitDescriptor<'b>>) -> BufferHandle {
         let desc = descriptor.into();
 
         let buffer = self.connection.device().create_buffer_init(&desc);
 
-        self.buffers.insert(self.next_buffer_id, buffer);
-        self.next_buffer_id += 1;
-        self.next_buffer_id - 1
+        let handle = self.buffers.insert(buffer, desc.label);
+        self.buffer_recipes.insert(handle, BufferRecipe::from_init_descriptor(&desc, self.recover_lost_device));
+        BufferHandle::new(handle)
+    }
+    ///Adds a new buffer to the heatwave window, starting with the values given.
+    ///
+    ///Returns a handle to the buffer that keeps the type of the values, for later access
+    pub fn add_buffer_from_slice<T: bytemuck::Pod>(&mut self, label: Option<&str>, contents: &[T], usage: wgpu::BufferUsages) -> BufferHandle<T> {
+        self.add_buffer_with_defaults(BufferInitDescriptor {
+            label,
+            contents: bytemuck::cast_slice(contents),
+            usage,
+        }).cast()
+    }
+    ///Adds a texture to the heatwave window, so it can be bound to bind groups with [`BindingResource::TextureView`] and [`BindingResource::TextureSampler`].
+    ///
+    ///Returns a handle to the texture, for later access. If a name is given, the texture can be looked up by it with [`HeatwaveApp::textures`]
+    ///
+    ///Textures can't be recreated if the device is lost, so they are removed along with the bind groups that use them.
+    pub fn add_texture(&mut self, texture: Texture, name: Option<&str>) -> TextureHandle {
+        self.textures.insert(texture, name)
+    }
+    ///Loads an image file into a texture with [`Texture::from_path`] and adds it to the heatwave window, named by the label of the options.
+    ///
+    ///Returns a handle to the texture, for later access. The pipelines that render mips are kept by the app, so they're only made once per format
+    ///
+    ///# Errors
+    /// Errors if the file can't be read or decoded, or if the image is empty or larger than the device supports
+    pub fn load_texture(&mut self, path: impl AsRef<std::path::Path>, options: &TextureOptions) -> Result<TextureHandle, TextureError> {
+        let image = image::open(path).map_err(TextureError::Image)?;
+        let texture = Texture::from_image_with(self.connection.device(), self.connection.queue(), &image, options, &mut self.mipmaps)?;
+        Ok(self.add_texture(texture, options.label))
+    }
+    ///Adds a new sampler to the heatwave window using the descriptor provided, so it can be bound to bind groups with [`BindingResource::Sampler`].
+    ///
+    ///Returns a handle to the sampler, for later access. The sampler can be looked up by its label with [`HeatwaveApp::samplers`]
+    pub fn add_sampler(&mut self, descriptor: &SamplerDescriptor) -> SamplerHandle {
+        let sampler = self.connection.device().create_sampler(descriptor);
+
+        let handle = self.samplers.insert(sampler, descriptor.label);
+        self.sampler_recipes.insert(handle, SamplerRecipe::from_descriptor(descriptor));
+        handle
+    }
+    ///Makes a bind group from registered resources, using the bind group layout at index `layout` of [`HeatwaveConfig::bind_groups`].\
+    ///Each resource is bound to the layout entry at the same position, so there must be one resource per entry.
+    ///
+    ///The group is drawn with at the layout's index. The first group made for each layout is bound straight away, and [`HeatwaveApp::set_bind_group`] swaps between them.
+    ///
+    ///Returns a handle to the bind group, for later access
+    ///
+    ///# Errors
+    /// Errors if the layout or any of the resources don't exist, if the number of resources doesn't match the layout, or if wgpu rejects the group
+    pub fn add_bind_group(&mut self, layout: usize, resources: &[BindingResource]) -> Result<BindGroupHandle, BindGroupError> {
+        let recipe = BindGroupRecipe { layout, resources: resources.to_vec() };
+        let bind_group = recipe.create(self.connection.device(), &self.bind_group_layouts, &self.buffers, &self.textures, &self.samplers)?;
+
+        let handle = self.bind_groups.insert(bind_group, None);
+        self.bound_groups[layout].get_or_insert(handle);
+        self.bind_group_recipes.insert(handle, recipe);
+        Ok(handle)
+    }
+    ///Binds the bind group at its layout's index for every draw made with the app's pipeline layout, replacing the group that was bound there.
+    ///
+    ///# Errors
+    /// Errors if the bind group doesn't exist
+    pub fn set_bind_group(&mut self, handle: BindGroupHandle) -> Result<(), BindGroupError> {
+        let recipe = self.bind_group_recipes.get(&handle).ok_or(BindGroupError::UnknownBindGroup(handle))?;
+        self.bound_groups[recipe.layout] = Some(handle);
+        Ok(())
     }
     ///Adds a new render pipeline to the heatwave window using the descriptor provided.
     ///
-    ///Returns the ID of the pipeline, for calling later
+    ///Returns a handle to the pipeline, for calling later. The pipeline can be looked up by its label with [`HeatwaveApp::render_pipelines`]
     ///
     /// # Substitution
     ///Fills in the layout with this heatwave instance's pipeline layout if none is provided
     ///
     ///Fills in a render target for the fragment shader is none are provided but a fragment shader is provided.\
     ///Uses a ColorTarget with the format of the heatwave instance's surface, a blend mode of Replace and targets all colour channels.
-    pub fn add_render_pipeline<'b>(&mut self, descriptor: impl Into<RenderPipelineDescriptor<'b>>) -> usize {
-        let mut desc: RenderPipelineDescriptor = descriptor.into();
+    ///
+    ///Pipelines using the heatwave instance's pipeline layout are drawn in its frames, so their multisample count is replaced with the frames' sample count.
+    ///
+    ///# Errors
+    /// Errors if wgpu rejects the descriptor, such as when the shader's inputs don't match the vertex buffers
+    pub fn add_render_pipeline<'b>(&mut self, descriptor: impl Into<RenderPipelineDescriptor<'b>>) -> Result<RenderPipelineHandle, PipelineError> {
+        self.add_render_pipeline_with_blend(descriptor.into(), wgpu::BlendState::REPLACE)
+    }
+    ///Adds the render pipeline like [`HeatwaveApp::add_render_pipeline`], but blends a filled in render target with the blend state given
+    pub(crate) fn add_render_pipeline_with_blend(&mut self, descriptor: RenderPipelineDescriptor, surface_blend: wgpu::BlendState) -> Result<RenderPipelineHandle, PipelineError> {
+        let mut desc: RenderPipelineDescriptor = descriptor; //Shortens the lifetime, so the layout and targets can be borrowed from here
+        let recipe = RenderPipelineRecipe::from_descriptor(&desc, &self.shaders, &self.pipeline_layouts, surface_blend);
+        let uses_app_layout = desc.layout.is_none();
         if desc.layout.is_none() {
             desc.layout = Some(&self.pipeline_layout);
+            desc.multisample.count = self.connection.sample_count();
         }
 
         let targets;
@@ -181,55 +486,469 @@ impl<'a> HeatwaveApp<'a> {
             if fragment.targets.is_empty() {
                 targets = [Some(wgpu::ColorTargetState {
                     format: self.connection.surface_config().format,
-                    blend: Some(wgpu::BlendState::REPLACE),
+                    blend: Some(surface_blend),
                     write_mask: wgpu::ColorWrites::ALL,
                 })];
                 fragment.targets = &targets;
             }
         }
-        let pipeline = self.connection.device().create_render_pipeline(&desc);
+        let device = self.connection.device();
+        let pipeline = gpu::capture_validation_error(device, || device.create_render_pipeline(&desc)).map_err(|error| PipelineError::new(desc.label, error))?;
 
-        self.render_pipelines.insert(self.next_render_id, pipeline);
-        self.next_render_id += 1;
-        self.next_render_id - 1
+        let handle = self.render_pipelines.insert(pipeline, desc.label);
+        if let Some(recipe) = recipe {
+            self.render_recipes.insert(handle, recipe);
+        }
+        if uses_app_layout {
+            self.app_layout_pipelines.insert(handle);
+        }
+        Ok(handle)
     }
     ///Adds a new compute pipeline to the heatwave window using the descriptor provided.
     ///
-    ///Returns the ID of the pipeline, for calling later
+    ///Returns a handle to the pipeline, for calling later. The pipeline can be looked up by its label with [`HeatwaveApp::compute_pipelines`]
     ///
     /// # Substitution
     /// Fills in the layout with this heatwave instance's pipeline layout if none is provided
-    pub fn add_compute_pipeline<'b>(&mut self, descriptor: impl Into<ComputePipelineDescriptor<'b>>) -> usize {
+    ///
+    ///# Errors
+    /// Errors if wgpu rejects the descriptor, such as when the shader's bindings don't match the layout
+    pub fn add_compute_pipeline<'b>(&mut self, descriptor: impl Into<ComputePipelineDescriptor<'b>>) -> Result<ComputePipelineHandle, PipelineError> {
         let mut desc: ComputePipelineDescriptor = descriptor.into();
+        let recipe = ComputePipelineRecipe::from_descriptor(&desc, &self.shaders, &self.pipeline_layouts);
         if desc.layout.is_none() {
             desc.layout = Some(&self.pipeline_layout);
         }
-        let pipeline = self.connection.device().create_compute_pipeline(&desc);
+        let device = self.connection.device();
+        let pipeline = gpu::capture_validation_error(device, || device.create_compute_pipeline(&desc)).map_err(|error| PipelineError::new(desc.label, error))?;
+
+        let handle = self.compute_pipelines.insert(pipeline, desc.label);
+        if let Some(recipe) = recipe {
+            self.compute_recipes.insert(handle, recipe);
+        }
+        Ok(handle)
+    }
+    ///Adds a pipeline layout for pipelines that bind different groups to the app's pipeline layout.
+    ///
+    ///Returns a handle to the layout. Use [`HeatwaveApp::pipeline_layout`] to get the layout for pipeline descriptors.\
+    ///Pipelines made with a registered layout are recreated when their shaders are reloaded. They can't be recreated if the device is lost, as the layout's bind group layouts belong to the user.
+    ///
+    ///The layout can be looked up by its label with [`HeatwaveApp::pipeline_layouts`]
+    pub fn add_pipeline_layout(&mut self, descriptor: &wgpu::PipelineLayoutDescriptor) -> PipelineLayoutHandle {
+        let layout = self.connection.device().create_pipeline_layout(descriptor);
+        self.pipeline_layouts.insert(Arc::new(layout), descriptor.label)
+    }
+
+    ///Removes the shader, returning it if the handle was still valid.\
+    ///Pipelines already made with the shader keep working, but can no longer be recreated if the device is lost
+    pub fn remove_shader(&mut self, handle: ShaderHandle) -> Option<Arc<wgpu::ShaderModule>> {
+        self.shader_recipes.remove(&handle);
+        self.shader_watcher.unwatch(handle);
+        self.shaders.remove(handle)
+    }
+    ///Removes the buffer, returning it if the handle was still valid.\
+    ///Bind groups already made with the buffer keep working, but can no longer be recreated if the device is lost
+    pub fn remove_buffer(&mut self, handle: impl Into<RawBufferHandle>) -> Option<wgpu::Buffer> {
+        let handle = handle.into();
+        self.buffer_recipes.remove(&handle);
+        self.buffers.remove(handle)
+    }
+    ///Replaces the buffer with a new one of the size given, keeping the handle valid.\
+    ///As much of the old contents as fits is copied across, and bind groups using the buffer are made again so they use the new one.
+    ///
+    ///The buffer must have been created with [`wgpu::BufferUsages::COPY_SRC`] and [`wgpu::BufferUsages::COPY_DST`] for the contents to be copied
+    pub(crate) fn resize_buffer(&mut self, handle: RawBufferHandle, size: wgpu::BufferAddress) -> Result<(), BufferWriteError> {
+        let old = self.buffers.get(handle).ok_or(BufferWriteError::UnknownBuffer(handle))?;
+        let recipe = self.buffer_recipes.get(&handle).ok_or(BufferWriteError::UnknownBuffer(handle))?.resized(size);
+        let device = self.connection.device();
+        let buffer = recipe.create(device);
+
+        let copy_size = old.size().min(size) / wgpu::COPY_BUFFER_ALIGNMENT * wgpu::COPY_BUFFER_ALIGNMENT;
+        if copy_size > 0 && old.usage().contains(wgpu::BufferUsages::COPY_SRC) && buffer.usage().contains(wgpu::BufferUsages::COPY_DST) {
+            let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("Heatwave Buffer Resize Encoder"),
+            });
+            encoder.copy_buffer_to_buffer(old, 0, &buffer, 0, copy_size);
+            self.connection.queue().submit(Some(encoder.finish()));
+        }
+        self.buffers.replace(handle, buffer);
+        self.buffer_recipes.insert(handle, recipe);
+
+        for (bind_group_handle, recipe) in &self.bind_group_recipes {
+            if !recipe.uses_buffer(handle) {
+                continue;
+            }
+            match recipe.create(self.connection.device(), &self.bind_group_layouts, &self.buffers, &self.textures, &self.samplers) {
+                Ok(bind_group) => {
+                    self.bind_groups.replace(*bind_group_handle, bind_group);
+                }
+                Err(error) => warn!("Bind group {bind_group_handle:?} couldn't be made again after its buffer was resized, so still uses the old buffer ({error})"),
+            }
+        }
+        Ok(())
+    }
+    ///Removes the texture, returning it if the handle was still valid
+    pub fn remove_texture(&mut self, handle: TextureHandle) -> Option<Texture> {
+        self.textures.remove(handle)
+    }
+    ///Removes the sampler, returning it if the handle was still valid
+    pub fn remove_sampler(&mut self, handle: SamplerHandle) -> Option<wgpu::Sampler> {
+        self.sampler_recipes.remove(&handle);
+        self.samplers.remove(handle)
+    }
+    ///Removes the bind group, returning it if the handle was still valid. If the group was bound, nothing is bound at its index afterwards
+    pub fn remove_bind_group(&mut self, handle: BindGroupHandle) -> Option<wgpu::BindGroup> {
+        self.bind_group_recipes.remove(&handle);
+        for bound in &mut self.bound_groups {
+            if *bound == Some(handle) {
+                *bound = None;
+            }
+        }
+        self.bind_groups.remove(handle)
+    }
+    ///Removes the render pipeline, returning it if the handle was still valid
+    pub fn remove_render_pipeline(&mut self, handle: RenderPipelineHandle) -> Option<wgpu::RenderPipeline> {
+        self.render_recipes.remove(&handle);
+        self.app_layout_pipelines.remove(&handle);
+        self.render_pipelines.remove(handle)
+    }
+    ///Removes the pipeline layout, returning it if the handle was still valid.\
+    ///Pipelines already made with the layout keep working, but are no longer recreated when their shaders are reloaded
+    pub fn remove_pipeline_layout(&mut self, handle: PipelineLayoutHandle) -> Option<Arc<wgpu::PipelineLayout>> {
+        self.pipeline_layouts.remove(handle)
+    }
+    ///Removes the compute pipeline, returning it if the handle was still valid
+    pub fn remove_compute_pipeline(&mut self, handle: ComputePipelineHandle) -> Option<wgpu::ComputePipeline> {
+        self.compute_recipes.remove(&handle);
+        self.compute_pipelines.remove(handle)
+    }
 
-        self.compute_pipelines.insert(self.next_compute_id, pipeline);
-        self.next_compute_id += 1;
-        self.next_compute_id - 1
+    ///Returns the shader module the handle refers to, if it exists. Useful for filling in pipeline descriptors
+    ///
+    ///The module is shared so it can be used in a descriptor while the app is borrowed mutably to add the pipeline.
+    pub fn shader(&self, handle: ShaderHandle) -> Option<Arc<wgpu::ShaderModule>> {
+        self.shaders.get(handle).cloned()
+    }
+    ///Returns the file the shader was loaded from, if it was loaded with [`HeatwaveApp::load_shader`]
+    pub fn shader_path(&self, handle: ShaderHandle) -> Option<&Path> {
+        self.shader_watcher.path(handle)
+    }
+    ///Reads the entry points, vertex inputs and bindings of a registered shader, returning `None` if the handle isn't valid or the shader wasn't made from WGSL.
+    ///
+    ///See [`ShaderReflection`] for making layouts from it
+    ///
+    ///# Errors
+    /// Errors if naga rejects the shader
+    pub fn reflect_shader(&self, handle: ShaderHandle) -> Option<Result<ShaderReflection, ReflectionError>> {
+        self.shader_recipes.get(&handle).map(|recipe| ShaderReflection::from_wgsl(recipe.source()))
+    }
+    ///Returns the texture the handle refers to, if it exists
+    pub fn texture(&self, handle: TextureHandle) -> Option<&Texture> {
+        self.textures.get(handle)
+    }
+    ///Returns the sampler the handle refers to, if it exists
+    pub fn sampler(&self, handle: SamplerHandle) -> Option<&wgpu::Sampler> {
+        self.samplers.get(handle)
+    }
+    ///Returns the bind group the handle refers to, if it exists. Useful for binding it to a single draw with [`rendering::DrawCall::bind_groups`]
+    pub fn bind_group(&self, handle: BindGroupHandle) -> Option<&wgpu::BindGroup> {
+        self.bind_groups.get(handle)
+    }
+    ///Returns the bind group layout at the index given of [`HeatwaveConfig::bind_groups`], if it exists. Useful for making bind groups by hand
+    pub fn bind_group_layout(&self, index: usize) -> Option<&wgpu::BindGroupLayout> {
+        self.bind_group_layouts.get(index).map(|(layout, _)| layout)
+    }
+    ///Returns the buffer the handle refers to, if it exists. Useful for binding buffers to bind groups
+    pub fn buffer(&self, handle: impl Into<RawBufferHandle>) -> Option<&wgpu::Buffer> {
+        self.buffers.get(handle.into())
+    }
+    ///Returns the render pipeline the handle refers to, if it exists
+    pub fn render_pipeline(&self, handle: RenderPipelineHandle) -> Option<&wgpu::RenderPipeline> {
+        self.render_pipelines.get(handle)
+    }
+    ///Returns the compute pipeline the handle refers to, if it exists
+    pub fn compute_pipeline(&self, handle: ComputePipelineHandle) -> Option<&wgpu::ComputePipeline> {
+        self.compute_pipelines.get(handle)
+    }
+    ///Returns the pipeline layout the handle refers to, if it exists. Useful for filling in pipeline descriptors
+    ///
+    ///The layout is shared so it can be used in a descriptor while the app is borrowed mutably to add the pipeline.
+    pub fn pipeline_layout(&self, handle: PipelineLayoutHandle) -> Option<Arc<wgpu::PipelineLayout>> {
+        self.pipeline_layouts.get(handle).cloned()
+    }
+    ///Every registered pipeline layout. Useful for looking layouts up by name
+    pub fn pipeline_layouts(&self) -> &Registry<Arc<wgpu::PipelineLayout>> {
+        &self.pipeline_layouts
+    }
+    ///Every registered shader. Useful for looking shaders up by name
+    pub fn shaders(&self) -> &Registry<Arc<wgpu::ShaderModule>> {
+        &self.shaders
+    }
+    ///Every registered buffer. Useful for looking buffers up by name
+    pub fn buffers(&self) -> &Registry<wgpu::Buffer> {
+        &self.buffers
+    }
+    ///Every registered texture. Useful for looking textures up by name
+    pub fn textures(&self) -> &Registry<Texture> {
+        &self.textures
+    }
+    ///Every registered sampler. Useful for looking samplers up by name
+    pub fn samplers(&self) -> &Registry<wgpu::Sampler> {
+        &self.samplers
+    }
+    ///Every registered render pipeline. Useful for looking pipelines up by name
+    pub fn render_pipelines(&self) -> &Registry<wgpu::RenderPipeline> {
+        &self.render_pipelines
+    }
+    ///Every registered compute pipeline. Useful for looking pipelines up by name
+    pub fn compute_pipelines(&self) -> &Registry<wgpu::ComputePipeline> {
+        &self.compute_pipelines
+    }
+
+    ///Writes the value to the start of the buffer. The write happens before the next work submitted to the GPU.
+    ///
+    ///The buffer must have been created with [`wgpu::BufferUsages::COPY_DST`]
+    ///
+    ///# Errors
+    /// Errors if the buffer doesn't exist, can't be written to, is too small, or if the size of `T` isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]
+    pub fn write_buffer<T: bytemuck::Pod>(&self, buffer: BufferHandle<T>, value: &T) -> Result<(), BufferWriteError> {
+        self.write_buffer_slice(buffer, 0, std::slice::from_ref(value))
+    }
+    ///Writes the values to the buffer, starting at the element `first_element`. The write happens before the next work submitted to the GPU.
+    ///
+    ///The buffer must have been created with [`wgpu::BufferUsages::COPY_DST`]
+    ///
+    ///# Errors
+    /// Errors if the buffer doesn't exist, can't be written to, or if the values would go past the end of it.\
+    /// Also errors if the byte offset or size of the write isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]
+    pub fn write_buffer_slice<T: bytemuck::Pod>(&self, buffer: BufferHandle<T>, first_element: usize, values: &[T]) -> Result<(), BufferWriteError> {
+        gpu::write_buffer(self.connection.queue(), &self.buffers, buffer.raw(), first_element, std::mem::size_of::<T>() as wgpu::BufferAddress, bytemuck::cast_slice(values))
+    }
+
+    ///Copies the value into the app's uniform ring, returning where it was put. Set the allocation on [`rendering::DrawCall::uniforms`] to bind it for a draw.
+    ///
+    ///The allocation is only valid until the frame is finished. See [`HeatwaveApp::finish_uniform_frame`]
+    ///
+    ///# Errors
+    /// Errors if [`HeatwaveConfig::uniform_ring`] isn't set, or if `T` is bigger than the ring's binding size
+    pub fn allocate_uniform<T: bytemuck::Pod>(&self, value: &T) -> Result<UniformAllocation, UniformRingError> {
+        let ring = self.uniform_ring.as_ref().ok_or(UniformRingError::Disabled)?;
+        ring.allocate(self.connection.device(), self.connection.queue(), bytemuck::bytes_of(value))
+    }
+    ///Marks the uniforms allocated since this was last called as used by the work submitted so far, so their space is reused once the GPU has finished it.
+    ///
+    ///[`HeatwaveRunner::run`] calls this after every frame. Headless apps and custom event handlers should call it after submitting each frame
+    pub fn finish_uniform_frame(&self) {
+        if let Some(ring) = &self.uniform_ring {
+            ring.finish_frame(self.connection.device(), self.connection.queue());
+        }
+    }
+    ///The group index the uniform ring is bound to in shaders, which is the group after the config's bind groups.\
+    ///`None` if [`HeatwaveConfig::uniform_ring`] isn't set
+    pub fn uniform_ring_group(&self) -> Option<u32> {
+        self.uniform_ring.as_ref().map(UniformRing::group)
+    }
+
+    ///Runs the compute dispatch on the GPU, without reading anything back
+    ///
+    ///# Errors
+    /// Errors if the dispatch references a pipeline that doesn't exist
+    pub fn dispatch(&self, dispatch: &ComputeDispatch) -> Result<(), ComputeError> {
+        gpu::submit_dispatch(self.connection.device(), self.connection.queue(), &self.compute_pipelines, &self.buffers, dispatch, None).map(|_| ())
+    }
+    ///Runs the compute dispatch on the GPU, then reads the contents of the output buffer back as a list of `T`.\
+    ///Blocks until the results are available. See [`HeatwaveApp::compute_async`] for the non-blocking version.
+    ///
+    ///The output buffer must have been created with [`wgpu::BufferUsages::COPY_SRC`], and be a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`] bytes long
+    ///
+    ///# Errors
+    /// Errors if the dispatch references a pipeline or buffer that doesn't exist, if the output buffer can't be copied from or isn't aligned, or if the results couldn't be mapped
+    pub fn compute<T: bytemuck::Pod>(&self, dispatch: &ComputeDispatch, output: BufferHandle<T>) -> Result<Vec<T>, ComputeError> {
+        self.compute_async(dispatch, output)?.block()
+    }
+    ///Runs the compute dispatch on the GPU, returning a future that resolves to the contents of the output buffer as a list of `T`.\
+    ///On native platforms, a helper thread waits on the device while the future is pending, so polling it doesn't spin the executor.
+    ///
+    ///The output buffer must have been created with [`wgpu::BufferUsages::COPY_SRC`], and be a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`] bytes long
+    ///
+    ///# Errors
+    /// Errors if the dispatch references a pipeline or buffer that doesn't exist, or if the output buffer can't be copied from or isn't aligned.\
+    /// The future errors if the results couldn't be mapped
+    pub fn compute_async<T: bytemuck::Pod>(&self, dispatch: &ComputeDispatch, output: BufferHandle<T>) -> Result<ComputeReadback<'_, T>, ComputeError> {
+        let staging = gpu::submit_dispatch(self.connection.device(), self.connection.queue(), &self.compute_pipelines, &self.buffers, dispatch, Some(output.raw()))?
+            .expect("Expected a staging buffer for a dispatch with an output");
+        Ok(ComputeReadback::new(self.connection.shared_device(), staging))
     }
 
     ///Finalises this app, returning a runnable version.\
     ///Changes can be made later on, but they must be done either during a render operation or on a user input.
+    ///
+    ///# Panics
+    /// Panics if the app is headless, as headless apps have no event loop to run.
     pub fn build_runner<P>(mut self, presenter: P) -> HeatwaveRunner<'a, P>
     where
         P: Presenter,
     {
         HeatwaveRunner {
             presenter,
-            event_loop: self.event_loop.take().expect("Expected valid event loop inside heatwave app"),
+            event_loop: self.event_loop.take().expect("Expected valid event loop inside heatwave app. Headless apps can't be run"),
             app: self,
         }
     }
 
-    ///Returns a thread safe reference to the window
-    pub fn window(&self) -> Arc<Window> {
+    ///Resizes the render targets of the app. This is done automatically by [`HeatwaveRunner::run`] when the window is resized, or its scale factor changes.
+    ///
+    ///See [`GpuConnection::resize`]
+    pub fn resize(&mut self, new_size: PhysicalSize<u32>) {
+        self.connection.resize(new_size);
+    }
+
+    ///Changes how frames are presented, such as with or without VSync. Returns the mode that was applied, which may differ if the surface doesn't support the one requested.
+    ///
+    ///To change this from the presenter while the app is running, use [`HeatwaveApp::present_settings`]
+    pub fn set_present_mode(&mut self, mode: wgpu::PresentMode) -> wgpu::PresentMode {
+        self.connection.set_present_mode(mode)
+    }
+    ///Changes how many frames the GPU may queue ahead of the one being shown.
+    ///
+    ///To change this from the presenter while the app is running, use [`HeatwaveApp::present_settings`]
+    pub fn set_frame_latency(&mut self, latency: u32) {
+        self.connection.set_frame_latency(latency);
+    }
+    ///Returns a thread safe handle for changing the present mode and frame latency while the app is running.\
+    ///Store this in your presenter to change them from event callbacks, such as a settings menu.
+    pub fn present_settings(&self) -> PresentSettings {
+        self.connection.present_settings()
+    }
+
+    ///Takes every error and device loss reported by the GPU since this was last called, oldest first.
+    ///
+    ///The default event handler does this before handling each event. Headless apps and custom handlers should call this regularly, such as once per frame.
+    pub fn poll_gpu_events(&self) -> Vec<GpuEvent> {
+        self.connection.poll_events()
+    }
+    ///Recreates the device after it was lost, along with every registered resource that can be recreated.
+    ///
+    ///The default event handler calls this when the device is lost if [`HeatwaveConfig::recover_lost_device`] is set.
+    ///
+    ///The bind group layouts, pipeline layout, shaders, buffers, samplers, bind groups and pipelines are recreated with the same handles. The uniform ring is recreated empty.\
+    ///Buffers made with [`HeatwaveApp::add_buffer_with_defaults`] get their starting values back if `recover_lost_device` is set, while other buffers are recreated empty. Anything written to them afterwards has to be written again.\
+    ///Textures, and pipelines made with a custom layout or a shader module that wasn't added with [`HeatwaveApp::add_shader`], can't be recreated, so they are removed.\
+    ///Anything made outside of Heatwave with the old device, such as bind groups, has to be made again.
+    ///
+    ///# Errors
+    /// Errors if a new device couldn't be created. Resources that fail to be recreated are logged and removed instead
+    pub async fn recover_device(&mut self) -> Result<(), GpuConnectionError> {
+        self.connection.recreate_device().await?;
+        let device = self.connection.device();
+
+        for (layout, recipe) in &mut self.bind_group_layouts {
+            *layout = recipe.create(device);
+        }
+        self.uniform_ring = self.uniform_ring.as_ref().map(|ring| ring.recreate(device));
+        self.mipmaps = MipmapGenerator::default();
+        self.pipeline_layout = Self::create_pipeline_layout(device, &self.bind_group_layouts, self.uniform_ring.as_ref(), &self.push_constants);
+
+        for (handle, module) in self.shaders.iter_mut() {
+            let recreated = match self.shader_recipes.get(&handle) {
+                Some(recipe) => recipe.create(device),
+                None => continue,
+            };
+            match recreated {
+                Ok(recreated) => *module = Arc::new(recreated),
+                Err(error) => {
+                    error!("Failed to recreate shader {handle:?} after device loss, removing it: {error}");
+                    self.shader_recipes.remove(&handle);
+                }
+            }
+        }
+        self.shaders.retain(|handle, _| self.shader_recipes.contains_key(&handle));
+
+        for (handle, buffer) in self.buffers.iter_mut() {
+            match self.buffer_recipes.get(&handle) {
+                Some(recipe) => *buffer = recipe.create(device),
+                None => warn!("Buffer {handle:?} can't be recreated after device loss, as it has no recipe. Removing it"),
+            }
+        }
+        self.buffers.retain(|handle, _| self.buffer_recipes.contains_key(&handle));
+        for (handle, sampler) in self.samplers.iter_mut() {
+            match self.sampler_recipes.get(&handle) {
+                Some(recipe) => *sampler = recipe.create(device),
+                None => warn!("Sampler {handle:?} can't be recreated after device loss, as it has no recipe. Removing it"),
+            }
+        }
+        self.samplers.retain(|handle, _| self.sampler_recipes.contains_key(&handle));
+        if !self.textures.is_empty() {
+            warn!("{} textures can't be recreated after device loss, removing them", self.textures.len());
+            self.textures.retain(|_, _| false);
+        }
+        if !self.pipeline_layouts.is_empty() {
+            warn!("{} pipeline layouts can't be recreated after device loss, as their bind group layouts belong to the user. Removing them", self.pipeline_layouts.len());
+            self.pipeline_layouts.retain(|_, _| false);
+        }
+
+        for (handle, bind_group) in self.bind_groups.iter_mut() {
+            let Some(recipe) = self.bind_group_recipes.get(&handle) else {
+                warn!("Bind group {handle:?} can't be recreated after device loss, as it has no recipe. Removing it");
+                continue;
+            };
+            match recipe.create(device, &self.bind_group_layouts, &self.buffers, &self.textures, &self.samplers) {
+                Ok(recreated) => *bind_group = recreated,
+                Err(error) => {
+                    warn!("Bind group {handle:?} can't be recreated after device loss, removing it: {error}");
+                    self.bind_group_recipes.remove(&handle);
+                }
+            }
+        }
+        self.bind_groups.retain(|handle, _| self.bind_group_recipes.contains_key(&handle));
+        for bound in &mut self.bound_groups {
+            *bound = bound.filter(|handle| self.bind_groups.contains(*handle));
+        }
+
+        let surface_format = self.connection.surface_config().format;
+        let sample_count = self.connection.sample_count();
+        for (handle, pipeline) in self.render_pipelines.iter_mut() {
+            let recipe = self.render_recipes.get(&handle).filter(|recipe| recipe.layout() == LayoutRecipe::App && recipe.shaders().all(|shader| self.shaders.contains(shader)));
+            match recipe.map(|recipe| recipe.create(device, &self.pipeline_layout, &self.shaders, surface_format, sample_count).map_err(|error| PipelineError::new(recipe.label(), error))) {
+                Some(Ok(recreated)) => *pipeline = recreated,
+                Some(Err(error)) => {
+                    error!("{error} again after device loss, removing it: {}", error.source);
+                    self.render_recipes.remove(&handle);
+                }
+                None => {
+                    warn!("Render pipeline {handle:?} can't be recreated after device loss, as it uses a custom layout or a missing shader. Removing it");
+                    self.render_recipes.remove(&handle);
+                }
+            }
+        }
+        self.render_pipelines.retain(|handle, _| self.render_recipes.contains_key(&handle));
+        self.app_layout_pipelines.retain(|handle| self.render_pipelines.contains(*handle));
+
+        for (handle, pipeline) in self.compute_pipelines.iter_mut() {
+            let recipe = self.compute_recipes.get(&handle).filter(|recipe| recipe.layout() == LayoutRecipe::App && self.shaders.contains(recipe.shader()));
+            match recipe.map(|recipe| recipe.create(device, &self.pipeline_layout, &self.shaders).map_err(|error| PipelineError::new(recipe.label(), error))) {
+                Some(Ok(recreated)) => *pipeline = recreated,
+                Some(Err(error)) => {
+                    error!("{error} again after device loss, removing it: {}", error.source);
+                    self.compute_recipes.remove(&handle);
+                }
+                None => {
+                    warn!("Compute pipeline {handle:?} can't be recreated after device loss, as it uses a custom layout or a missing shader. Removing it");
+                    self.compute_recipes.remove(&handle);
+                }
+            }
+        }
+        self.compute_pipelines.retain(|handle, _| self.compute_recipes.contains_key(&handle));
+
+        Ok(())
+    }
+
+    ///Returns a thread safe reference to the window, or `None` if the app is headless
+    pub fn window(&self) -> Option<Arc<Window>> {
         self.window.clone()
     }
 
-    pub fn connection(&self) -> &GpuConnection {
+    pub fn connection(&self) -> &GpuConnection<'a> {
         &self.connection
     }
 }
@@ -288,6 +1007,11 @@ where
             WindowEvent::Moved(position) => presenter.on_window_move(position),
             WindowEvent::ScaleFactorChanged { scale_factor, inner_size_writer } => presenter.on_scale_factor_change(scale_factor, inner_size_writer),
             WindowEvent::Touch(touch) => presenter.on_touch(touch),
+            WindowEvent::GpuError(error) => presenter.on_gpu_error(error),
+            WindowEvent::DeviceLost(error) => presenter.on_device_lost(error),
+            WindowEvent::DeviceRecovered => presenter.on_device_recovered(),
+            WindowEvent::ShaderReloaded(shader) => presenter.on_shader_reloaded(shader),
+            WindowEvent::ShaderError(error) => presenter.on_shader_error(error),
             WindowEvent::RequestRenderData => {
                 return UserResponse::RenderDataPrepared(presenter.package_render_data());
             }
@@ -306,10 +1030,34 @@ where
     ///`sender` sends messages to the user thread, `receiver` receives messages back from the user thread.
     ///
     ///Public only for people using [`HeatwaveRunner::run_custom`] to use as a fallback/base implementation for their custom handlers
-    pub fn default_handler_render(args: EventArgs, app: &HeatwaveApp, sender: &Sender<WindowEvent>, receiver: &Receiver<UserResponse<<Handler as Presenter>::RenderData>>) {
+    pub fn default_handler_render(args: EventArgs, app: &mut HeatwaveApp, sender: &Sender<WindowEvent>, receiver: &Receiver<UserResponse<<Handler as Presenter>::RenderData>>) {
+        for event in app.poll_gpu_events() {
+            if !Self::default_handler_gpu(event, app, sender) {
+                args.target.exit();
+                return;
+            }
+        }
+
         match args.event {
-            Event::WindowEvent { window_id, event } if window_id == app.window().id() => match event {
+            Event::WindowEvent { window_id, event } if app.window().is_some_and(|window| window.id() == window_id) => match event {
                 winit::event::WindowEvent::RedrawRequested => {
+                    if app.connection().is_minimised() {
+                        return; //There's nothing to draw to, so don't bother the user thread
+                    }
+                    app.connection.apply_present_settings();
+                    if app.shader_watcher.is_due() {
+                        for result in app.reload_shaders() {
+                            let event = match result {
+                                Ok(shader) => WindowEvent::ShaderReloaded(shader),
+                                Err(error) => WindowEvent::ShaderError(error),
+                            };
+                            if sender.send(event).is_err() {
+                                error!("User thread disconnected early! Shutting down window (Failed to send shader reload)");
+                                args.target.exit();
+                                return;
+                            }
+                        }
+                    }
                     if sender.send(WindowEvent::RequestRenderData).is_err() {
 						error!("User thread disconnected early! Shutting down window (Failed to request render data)");
 						args.target.exit();
@@ -319,7 +1067,16 @@ where
                         let response = receiver.recv_timeout(Duration::from_millis(400));
                         if let Ok(response) = response {
                             if let UserResponse::RenderDataPrepared(render_data) = response {
-                                Handler::render(render_data, Some(RenderHelper::new(app)));
+                                let helper = RenderHelper::new(app);
+                                let fatal_error = helper.as_ref().err().filter(|error| error.is_fatal()).copied();
+
+                                Handler::render(render_data, helper);
+                                app.finish_uniform_frame();
+
+                                if let Some(error) = fatal_error {
+                                    error!("Failed to acquire frame ({error}), which can't be recovered from. Shutting down window");
+                                    args.target.exit();
+                                }
                                 break;
                             }
                         } else {
@@ -347,6 +1104,15 @@ where
                     }
                 }
                 event => {
+                    match &event {
+                        winit::event::WindowEvent::Resized(size) => app.resize(*size),
+                        winit::event::WindowEvent::ScaleFactorChanged { .. } => {
+                            if let Some(window) = app.window() {
+                                app.resize(window.inner_size());
+                            }
+                        }
+                        _ => {}
+                    }
                     if sender.send(event.into()).is_err() {
 						error!("User thread disconnected early! Shutting down window (Failed to send events)");
 						args.target.exit()
@@ -356,6 +1122,38 @@ where
             _ => {}
         };
     }
+    ///Forwards an event reported by the GPU to the user thread, recovering the device if it was lost and [`HeatwaveConfig::recover_lost_device`] is set.
+    ///
+    ///Returns false if the app can't continue.
+    fn default_handler_gpu(event: GpuEvent, app: &mut HeatwaveApp, sender: &Sender<WindowEvent>) -> bool {
+        let lost = match event {
+            GpuEvent::Error(error) => {
+                return sender.send(WindowEvent::GpuError(error)).is_ok();
+            }
+            GpuEvent::DeviceLost(lost) => lost,
+        };
+
+        error!("{lost}");
+        if sender.send(WindowEvent::DeviceLost(lost)).is_err() {
+            error!("User thread disconnected early! Shutting down window (Failed to send device loss)");
+            return false;
+        }
+        if !app.recover_lost_device {
+            error!("Device recovery is disabled. Shutting down window");
+            return false;
+        }
+
+        match pollster::block_on(app.recover_device()) {
+            Ok(()) => {
+                info!("Recovered from device loss using {}", app.connection().adapter_info().name);
+                sender.send(WindowEvent::DeviceRecovered).is_ok()
+            }
+            Err(error) => {
+                error!("Failed to recover from device loss ({error}). Shutting down window");
+                false
+            }
+        }
+    }
     ///Runs the app, opening the window and starting the event loop. This runs with some premade event loop handler and instructions that make use of the presenter in the documented ways.\
     /// The default event loop handler is designed to be stable, lightweight and a suitable fit for most applications. It should handle edge cases gracefully.
     ///
@@ -363,7 +1161,7 @@ where
     ///
     /// This consumes the HeatwaveApp object, and occupies the running thread.\
     /// Rendering and user input handling are on separate threads.
-    pub fn run(mut self) -> Result<(), EventLoopError> {
+    pub fn run(mut self) -> Result<(), HeatwaveError> {
         let (sender_user_to_render, receiver_user_to_render) = channel();
         let (sender_render_to_user, receiver_render_to_user) = channel();
 
@@ -403,17 +1201,21 @@ where
 		let receiver_ref = &receiver_user_to_render;
 
         self.event_loop.run(move |event, target| {
-            Self::default_handler_render(EventArgs { event, target }, &self.app, sender_ref, receiver_ref);
-        })
+            Self::default_handler_render(EventArgs { event, target }, &mut self.app, sender_ref, receiver_ref);
+        })?;
+        Ok(())
     }
     ///Runs the app, opening the window and starting the event loop. This runs with the provided event loop handler, and as such makes no guarantees about the interaction with the presenter.
-    pub fn run_custom<HandleFn>(mut self, handler: HandleFn) -> Result<(), EventLoopError>
+    ///
+    /// The handler is given mutable access to the app, so it is responsible for calling [`HeatwaveApp::resize`] when the window resizes.
+    pub fn run_custom<HandleFn>(mut self, mut handler: HandleFn) -> Result<(), HeatwaveError>
     where
-        HandleFn: Fn(EventArgs, &HeatwaveApp, &mut Handler),
+        HandleFn: FnMut(EventArgs, &mut HeatwaveApp, &mut Handler),
     {
         self.event_loop.run(move |event, target| {
-            handler(EventArgs { event, target }, &self.app, &mut self.presenter);
-        })
+            handler(EventArgs { event, target }, &mut self.app, &mut self.presenter);
+        })?;
+        Ok(())
     }
 
 	///Dissolves the runner into its basic components, in the event that features like run_custom is not appropriate for your use case.
@@ -472,6 +1274,17 @@ pub enum WindowEvent {
     ///This is raised for events that aren't handled by Heatwave (usually due to being widely unsupported, such as Mac forcetouch events)
     Unknown,
 
+    ///Raised when wgpu reports an error that wasn't captured, such as a validation error in submitted work
+    GpuError(GpuError),
+    ///Raised when the GPU device is lost. If [`HeatwaveConfig::recover_lost_device`] is set, [`WindowEvent::DeviceRecovered`] follows once it has been recreated
+    DeviceLost(DeviceLostError),
+    ///Raised when the device has been recreated after being lost
+    DeviceRecovered,
+    ///Raised when a shader loaded with [`HeatwaveApp::load_shader`] has been reloaded after its file changed, along with its pipelines
+    ShaderReloaded(ShaderHandle),
+    ///Raised when a changed shader file couldn't be reloaded. The previous shader and pipelines are still in use
+    ShaderError(ShaderFileError),
+
     ///Raised when the window needs render data to render the next frame.
     ///
     ///In the default event handler, the event loop is blocked until [`UserEvent::RenderDataPrepared`] is sent back
@@ -545,14 +1358,46 @@ pub struct HeatwaveConfig<'a> {
     ///
     ///Defaults to HighPerformance
     pub power_preference: wgpu::PowerPreference,
+    ///Which graphics APIs may be used to talk to the GPU. Include `Backends::GL` to allow OpenGL/WebGL.
+    ///
+    ///Defaults to `None`, which uses `Backends::PRIMARY`, or every backend when headless
+    pub backends: Option<wgpu::Backends>,
+    ///Whether to use the fallback adapter, which is usually a software renderer running on the CPU.
+    ///
+    ///Defaults to `false`. Headless apps use the fallback adapter anyway if no other adapter is found
+    pub force_fallback_adapter: bool,
+    ///Picks which adapter to use from the list of adapters available on the chosen backends, by returning its index.\
+    ///Only adapters compatible with the window are listed. Returning `None` fails initialisation.
+    ///
+    ///Defaults to `None`, letting wgpu pick an adapter using `power_preference`. See [`GpuConnection::enumerate_adapters`]
+    ///
+    ///# Usage
+    ///```rs
+    /// let config = HeatwaveConfig {
+    ///     adapter_selector: Some(|adapters| adapters.iter().position(|info| info.device_type == wgpu::DeviceType::IntegratedGpu)),
+    ///     ..Default::default()
+    /// };
+    ///```
+    pub adapter_selector: Option<AdapterSelector>,
     ///The title of the window. Also used in diagnostics.
     ///
     ///Defaults to "Heatwave App"
     pub name: String,
-    ///Which additional GPU features you require (Features that aren't supported in all contexts)
+    ///Which additional GPU features you require (Features that aren't supported in all contexts).\
+    ///Initialisation fails if the adapter doesn't support all of them.
     ///
     ///Defaults to empty (No additional features)
-    pub features: wgpu::Features,
+    pub required_features: wgpu::Features,
+    ///Which additional GPU features to enable if the adapter supports them.\
+    ///Check which were enabled with [`GpuConnection::has_features`] to pick code paths at runtime.
+    ///
+    ///Defaults to empty (No additional features)
+    pub optional_features: wgpu::Features,
+    ///Which limits to request from the device. Initialisation fails if the adapter can't meet them.\
+    ///Use [`LimitsPreset::Downlevel`] or [`LimitsPreset::WebGl2`] to support older hardware.
+    ///
+    ///Defaults to `LimitsPreset::Default`
+    pub limits: LimitsPreset,
     /// The size the window opens with (excluding decorations)
     ///
     /// Defaults to 200x200
@@ -617,7 +1462,9 @@ pub struct HeatwaveConfig<'a> {
     ///
     /// Defaults to `false`, though this behaviour might vary on platform.
     pub active_on_open: bool,
-    /// Adds bind groups to shader sets.
+    /// The layouts of the bind groups used by shaders, making up the app's pipeline layout. Each layout's index in the list is its group index in shaders.
+    ///
+    /// Make bind groups for these with [`HeatwaveApp::add_bind_group`].
     ///
     /// Defaults to an empty list (no bind groups)
     pub bind_groups: &'a [BindGroupLayoutDescriptor<'a>],
@@ -625,17 +1472,55 @@ pub struct HeatwaveConfig<'a> {
     ///
     /// Defaults to an empty list.
     ///
-    /// **The feature "Feature::PUSHCONSTANTS` must be enabled for this to work**
+    /// **The feature `Features::PUSH_CONSTANTS` must be in `required_features` for this to work**
     pub push_constants: &'a [PushConstantRange],
     ///What to render behind everything
     pub skybox: wgpu::Color,
+    ///How frames are presented to the window. Immediate and Mailbox disable VSync, while Fifo and FifoRelaxed enable it.\
+    ///If the surface doesn't support the mode chosen, the closest supported mode is used instead.
+    ///
+    ///Defaults to `AutoVsync`
+    pub present_mode: wgpu::PresentMode,
+    ///How many frames the GPU may queue up ahead of the one being shown. Lower values reduce input latency.
+    ///
+    ///Defaults to 2
+    pub frame_latency: u32,
+    ///Whether the app should run without a window, drawing into an offscreen texture instead.\
+    ///The offscreen texture is sized by `default_size`. See [`HeatwaveApp::new_headless`]
+    ///
+    ///Defaults to `false`
+    pub headless: bool,
+    ///Whether to recreate the device if it is lost, such as when the GPU driver restarts, rather than closing the app.\
+    ///Registered shaders, buffers and pipelines are recreated with it. See [`HeatwaveApp::recover_device`]
+    ///
+    ///While this is set, buffers made with starting values keep a copy of them on the CPU, so they can be refilled.
+    ///
+    ///Defaults to `false`
+    pub recover_lost_device: bool,
+    ///Gives the app a ring of uniform buffers to allocate per-frame uniforms from, such as camera and per-object data. See [`HeatwaveApp::allocate_uniform`]
+    ///
+    ///The ring is bound with a dynamic offset at the group after the last of `bind_groups`, as a uniform buffer at binding 0.\
+    ///The app fails to initialise if its sizes are larger than the device's `max_uniform_buffer_binding_size`, or aren't multiples of its `min_uniform_buffer_offset_alignment`.
+    ///
+    ///Defaults to `None` (no uniform ring)
+    pub uniform_ring: Option<UniformRingConfig>,
+    ///How many samples to take per pixel to smooth jagged edges. Must be 1 (no MSAA), 2, 4 or 8.\
+    ///If the adapter doesn't support the count for the surface format, the highest supported count below it is used instead. See [`GpuConnection::sample_count`]
+    ///
+    ///Defaults to 1
+    pub msaa_samples: u32,
 }
 impl<'a> Default for HeatwaveConfig<'a> {
     fn default() -> Self {
         Self {
             power_preference: wgpu::PowerPreference::HighPerformance, //Default to the best GPU it can find, as this is the most common one to ask for
+            backends: None,
+            force_fallback_adapter: false,
+            adapter_selector: None,
             name: String::from("Heatwave App"),
-            features: Features::empty(),
+            required_features: Features::empty(),
+            optional_features: Features::empty(),
+            limits: LimitsPreset::Default,
             default_size: winit::dpi::PhysicalSize::new(200, 200),
             minimum_size: None,
             maximum_size: None,
@@ -653,14 +1538,190 @@ impl<'a> Default for HeatwaveConfig<'a> {
             bind_groups: &[],
             push_constants: &[],
             skybox: wgpu::Color { a: 1.0, r: 0.5, g: 0.5, b: 0.5 },
+            present_mode: wgpu::PresentMode::AutoVsync,
+            frame_latency: 2,
+            headless: false,
+            recover_lost_device: false,
+            uniform_ring: None,
+            msaa_samples: 1,
+        }
+    }
+}
+
+impl HeatwaveConfig<'_> {
+    ///Checks the config for combinations of settings that would fail or be ignored during initialisation.
+    ///
+    ///This is run automatically when creating a [`HeatwaveApp`]. Warnings are logged, while errors stop the app from being created.\
+    ///Some problems can only be found once the surface exists, and are logged as warnings during initialisation instead.
+    pub fn validate(&self) -> ConfigValidation {
+        let mut issues = Vec::new();
+
+        if !self.push_constants.is_empty() && !self.required_features.contains(Features::PUSH_CONSTANTS) {
+            issues.push(ConfigIssue::PushConstantsWithoutFeature);
+        }
+        if let (Some(minimum), Some(maximum)) = (self.minimum_size, self.maximum_size) {
+            if minimum.width > maximum.width || minimum.height > maximum.height {
+                issues.push(ConfigIssue::MinimumLargerThanMaximum { minimum, maximum });
+            }
+        }
+        let below_minimum = self.minimum_size.is_some_and(|minimum| self.default_size.width < minimum.width || self.default_size.height < minimum.height);
+        let above_maximum = self.maximum_size.is_some_and(|maximum| self.default_size.width > maximum.width || self.default_size.height > maximum.height);
+        if below_minimum || above_maximum {
+            issues.push(ConfigIssue::DefaultSizeOutOfBounds(self.default_size));
+        }
+        if self.headless && (self.default_size.width == 0 || self.default_size.height == 0) {
+            issues.push(ConfigIssue::ZeroSizedOffscreenTarget);
+        }
+        if let Some(ring) = self.uniform_ring {
+            if ring.binding_size == 0 || ring.binding_size > ring.block_size {
+                issues.push(ConfigIssue::InvalidUniformRing { binding_size: ring.binding_size, block_size: ring.block_size });
+            }
+        }
+        if ![1, 2, 4, 8].contains(&self.msaa_samples) {
+            issues.push(ConfigIssue::InvalidMsaaSamples(self.msaa_samples));
         }
+        for (index, layout) in self.bind_groups.iter().enumerate() {
+            if layout.entries.is_empty() || layout.entries.iter().all(|entry| entry.visibility.is_empty()) {
+                issues.push(ConfigIssue::InvisibleBindGroup(index));
+            }
+        }
+
+        ConfigValidation { issues }
+    }
+    ///Checks the config against the capabilities of the surface it will be used with
+    pub fn validate_surface(&self, capabilities: &wgpu::SurfaceCapabilities) -> ConfigValidation {
+        let mut issues = Vec::new();
+
+        if self.transparent && capabilities.alpha_modes.iter().all(|mode| *mode == wgpu::CompositeAlphaMode::Opaque) {
+            issues.push(ConfigIssue::TransparencyUnsupported);
+        }
+
+        ConfigValidation { issues }
     }
 }
 
+///The problems found in a [`HeatwaveConfig`] by [`HeatwaveConfig::validate`]
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ConfigValidation {
+    ///Every problem found, in the order they were found
+    pub issues: Vec<ConfigIssue>,
+}
+impl ConfigValidation {
+    ///Returns true if any of the problems would stop the app from being created
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+    ///The problems that stop the app from being created
+    pub fn errors(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().filter(|issue| issue.severity() == IssueSeverity::Error)
+    }
+    ///The problems that don't stop the app from being created, but are likely mistakes
+    pub fn warnings(&self) -> impl Iterator<Item = &ConfigIssue> {
+        self.issues.iter().filter(|issue| issue.severity() == IssueSeverity::Warning)
+    }
+    ///Logs every warning, then errors if there are any errors
+    pub fn check(self) -> Result<(), HeatwaveInitialiseError> {
+        for warning in self.warnings() {
+            warn!("Heatwave config: {warning}");
+        }
+        if self.has_errors() {
+            return Err(HeatwaveInitialiseError::InvalidConfig(self));
+        }
+        Ok(())
+    }
+    fn check_window_issues(self) -> Result<(), HeatwaveInitialiseError> {
+        ConfigValidation { issues: self.issues.into_iter().filter(ConfigIssue::is_window_related).collect() }.check()
+    }
+    fn check_graphics_issues(self) -> Result<(), HeatwaveInitialiseError> {
+        ConfigValidation { issues: self.issues.into_iter().filter(|issue| !issue.is_window_related()).collect() }.check()
+    }
+}
+///A single problem with a [`HeatwaveConfig`]
+#[derive(Clone, PartialEq, Debug)]
+pub enum ConfigIssue {
+    ///`push_constants` is set, but `Features::PUSH_CONSTANTS` isn't in `required_features`
+    PushConstantsWithoutFeature,
+    ///`minimum_size` is larger than `maximum_size` in at least one dimension
+    MinimumLargerThanMaximum { minimum: PhysicalSize<u32>, maximum: PhysicalSize<u32> },
+    ///`default_size` is outside the bounds of `minimum_size` and `maximum_size`, so the platform will resize it
+    DefaultSizeOutOfBounds(PhysicalSize<u32>),
+    ///The app is headless, but `default_size` has a width or height of 0
+    ZeroSizedOffscreenTarget,
+    ///The bind group layout at this index has no entries, or no entries visible to any shader stage
+    InvisibleBindGroup(usize),
+    ///`transparent` is set, but the surface only supports opaque alpha modes
+    TransparencyUnsupported,
+    ///`uniform_ring` binds nothing, or binds more than fits in one of its buffers
+    InvalidUniformRing { binding_size: wgpu::BufferAddress, block_size: wgpu::BufferAddress },
+    ///`msaa_samples` isn't 1, 2, 4 or 8
+    InvalidMsaaSamples(u32),
+}
+impl Display for ConfigIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigIssue::PushConstantsWithoutFeature => write!(f, "Push constant ranges are set, but Features::PUSH_CONSTANTS isn't required"),
+            ConfigIssue::MinimumLargerThanMaximum { minimum, maximum } => write!(
+                f,
+                "The minimum size ({}x{}) is larger than the maximum size ({}x{})",
+                minimum.width, minimum.height, maximum.width, maximum.height
+            ),
+            ConfigIssue::DefaultSizeOutOfBounds(size) => write!(f, "The default size ({}x{}) is outside the minimum and maximum sizes", size.width, size.height),
+            ConfigIssue::ZeroSizedOffscreenTarget => write!(f, "The app is headless, but the default size has a width or height of 0"),
+            ConfigIssue::InvisibleBindGroup(index) => write!(f, "Bind group layout {index} has no entries visible to any shader stage"),
+            ConfigIssue::TransparencyUnsupported => write!(f, "The window is transparent, but the surface only supports opaque alpha modes"),
+            ConfigIssue::InvalidUniformRing { binding_size, block_size } => write!(f, "The uniform ring binds {binding_size} bytes, which must be more than 0 and fit in its {block_size} byte buffers"),
+            ConfigIssue::InvalidMsaaSamples(samples) => write!(f, "MSAA is set to {samples} samples, but must be 1, 2, 4 or 8"),
+        }
+    }
+}
+impl Display for ConfigValidation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Invalid config: ")?;
+        for (index, issue) in self.errors().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+impl Error for ConfigValidation {}
+///How serious a [`ConfigIssue`] is
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IssueSeverity {
+    ///The app can still be created, but the config is likely a mistake
+    Warning,
+    ///The app can't be created with this config
+    Error,
+}
+impl ConfigIssue {
+    pub fn severity(&self) -> IssueSeverity {
+        match self {
+            ConfigIssue::PushConstantsWithoutFeature
+            | ConfigIssue::MinimumLargerThanMaximum { .. }
+            | ConfigIssue::ZeroSizedOffscreenTarget
+            | ConfigIssue::InvalidUniformRing { .. }
+            | ConfigIssue::InvalidMsaaSamples(_) => IssueSeverity::Error,
+            ConfigIssue::DefaultSizeOutOfBounds(_) | ConfigIssue::InvisibleBindGroup(_) | ConfigIssue::TransparencyUnsupported => IssueSeverity::Warning,
+        }
+    }
+    ///Returns true if the issue only relates to settings that are ignored by [`HeatwaveApp::new_with_window`]
+    pub fn is_window_related(&self) -> bool {
+        matches!(self, ConfigIssue::MinimumLargerThanMaximum { .. } | ConfigIssue::DefaultSizeOutOfBounds(_))
+    }
+}
+
+///Describes why a [`HeatwaveApp`] couldn't be created
+#[derive(Debug)]
 pub enum HeatwaveInitialiseError {
     GpuConnection(GpuConnectionError),
+    ///The config has problems that would stop the app from working. See [`HeatwaveConfig::validate`]
+    InvalidConfig(ConfigValidation),
     EventLoopCreation(EventLoopError),
     WindowCreation(OsError),
+    ///The uniform ring doesn't fit the device's limits
+    UniformRing(UniformRingError),
 }
 impl From<GpuConnectionError> for HeatwaveInitialiseError {
     fn from(value: GpuConnectionError) -> Self {
@@ -677,3 +1738,288 @@ impl From<OsError> for HeatwaveInitialiseError {
         HeatwaveInitialiseError::WindowCreation(value)
     }
 }
+impl From<UniformRingError> for HeatwaveInitialiseError {
+    fn from(value: UniformRingError) -> Self {
+        HeatwaveInitialiseError::UniformRing(value)
+    }
+}
+impl Display for HeatwaveInitialiseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeatwaveInitialiseError::GpuConnection(error) => write!(f, "Failed to connect to the GPU: {error}"),
+            HeatwaveInitialiseError::InvalidConfig(validation) => write!(f, "{validation}"),
+            HeatwaveInitialiseError::EventLoopCreation(_) => write!(f, "Failed to create the event loop"),
+            HeatwaveInitialiseError::WindowCreation(_) => write!(f, "Failed to create the window"),
+            HeatwaveInitialiseError::UniformRing(_) => write!(f, "Failed to create the uniform ring"),
+        }
+    }
+}
+impl Error for HeatwaveInitialiseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HeatwaveInitialiseError::GpuConnection(error) => Some(error),
+            HeatwaveInitialiseError::InvalidConfig(validation) => Some(validation),
+            HeatwaveInitialiseError::EventLoopCreation(error) => Some(error),
+            HeatwaveInitialiseError::WindowCreation(error) => Some(error),
+            HeatwaveInitialiseError::UniformRing(error) => Some(error),
+        }
+    }
+}
+
+///Describes why wgpu rejected a pipeline
+#[derive(Debug)]
+pub struct PipelineError {
+    ///The label of the pipeline, if it had one
+    pub label: Option<String>,
+    ///The validation error reported by wgpu
+    pub source: wgpu::Error,
+}
+impl PipelineError {
+    fn new(label: Option<&str>, source: wgpu::Error) -> Self {
+        PipelineError { label: label.map(String::from), source }
+    }
+}
+impl Display for PipelineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "Failed to create pipeline \"{label}\""),
+            None => write!(f, "Failed to create unlabelled pipeline"),
+        }
+    }
+}
+impl Error for PipelineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+///Describes why wgpu rejected a shader module
+#[derive(Debug)]
+pub struct ShaderError {
+    ///The label of the shader, if it had one
+    pub label: Option<String>,
+    ///The validation error reported by wgpu
+    pub source: wgpu::Error,
+}
+impl Display for ShaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.label {
+            Some(label) => write!(f, "Failed to create shader \"{label}\""),
+            None => write!(f, "Failed to create unlabelled shader"),
+        }
+    }
+}
+impl Error for ShaderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+///Describes why the GPU device was lost
+#[derive(Clone, Debug)]
+pub struct DeviceLostError {
+    ///Why wgpu reported the device as lost
+    pub reason: wgpu::DeviceLostReason,
+    ///The message reported alongside the loss
+    pub message: String,
+}
+impl Display for DeviceLostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The GPU device was lost ({:?}): {}", self.reason, self.message)
+    }
+}
+impl PartialEq for DeviceLostError {
+    fn eq(&self, other: &Self) -> bool {
+        //DeviceLostReason doesn't implement PartialEq
+        std::mem::discriminant(&self.reason) == std::mem::discriminant(&other.reason) && self.message == other.message
+    }
+}
+impl Error for DeviceLostError {}
+
+///Any error produced by Heatwave, for when a single error type is more convenient than the specific ones
+#[derive(Debug)]
+pub enum HeatwaveError {
+    ///The app couldn't be created
+    Initialise(HeatwaveInitialiseError),
+    ///A frame couldn't be acquired from the surface
+    Frame(FrameError),
+    ///The GPU device was lost
+    DeviceLost(DeviceLostError),
+    ///wgpu rejected a shader module
+    Shader(ShaderError),
+    ///A shader file couldn't be loaded or reloaded
+    ShaderFile(ShaderFileError),
+    ///wgpu rejected a pipeline
+    Pipeline(PipelineError),
+    ///A bind group couldn't be made or bound
+    BindGroup(BindGroupError),
+    ///A frame couldn't be drawn
+    Render(RenderError),
+    ///A compute dispatch or its readback failed
+    Compute(ComputeError),
+    ///A buffer couldn't be written to
+    BufferWrite(BufferWriteError),
+    ///A uniform couldn't be allocated from the uniform ring
+    Uniform(UniformRingError),
+    ///Space couldn't be allocated from or returned to a buffer heap
+    Heap(HeapError),
+    ///wgpu reported an error that wasn't captured
+    Gpu(GpuError),
+    ///A texture couldn't be made from an image
+    Texture(TextureError),
+    ///An image couldn't be added to a texture atlas
+    Atlas(AtlasError),
+    ///A shader couldn't be preprocessed
+    Preprocess(PreprocessError),
+    ///A shader couldn't be reflected
+    Reflection(ReflectionError),
+    ///A vertex buffer layout doesn't match a vertex shader's inputs
+    VertexLayout(VertexLayoutError),
+    ///The event loop failed while running
+    EventLoop(EventLoopError),
+}
+impl Display for HeatwaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HeatwaveError::Initialise(error) => write!(f, "{error}"),
+            HeatwaveError::Frame(error) => write!(f, "{error}"),
+            HeatwaveError::DeviceLost(error) => write!(f, "{error}"),
+            HeatwaveError::Shader(error) => write!(f, "{error}"),
+            HeatwaveError::ShaderFile(error) => write!(f, "{error}"),
+            HeatwaveError::Pipeline(error) => write!(f, "{error}"),
+            HeatwaveError::BindGroup(error) => write!(f, "{error}"),
+            HeatwaveError::Render(error) => write!(f, "{error}"),
+            HeatwaveError::Compute(error) => write!(f, "{error}"),
+            HeatwaveError::BufferWrite(error) => write!(f, "{error}"),
+            HeatwaveError::Uniform(error) => write!(f, "{error}"),
+            HeatwaveError::Heap(error) => write!(f, "{error}"),
+            HeatwaveError::Gpu(error) => write!(f, "{error}"),
+            HeatwaveError::Texture(error) => write!(f, "{error}"),
+            HeatwaveError::Atlas(error) => write!(f, "{error}"),
+            HeatwaveError::Preprocess(error) => write!(f, "{error}"),
+            HeatwaveError::Reflection(error) => write!(f, "{error}"),
+            HeatwaveError::VertexLayout(error) => write!(f, "{error}"),
+            HeatwaveError::EventLoop(error) => write!(f, "The event loop failed: {error}"),
+        }
+    }
+}
+impl Error for HeatwaveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            HeatwaveError::Initialise(error) => Some(error),
+            HeatwaveError::Frame(error) => Some(error),
+            HeatwaveError::DeviceLost(error) => Some(error),
+            HeatwaveError::Shader(error) => Some(error),
+            HeatwaveError::ShaderFile(error) => Some(error),
+            HeatwaveError::Pipeline(error) => Some(error),
+            HeatwaveError::BindGroup(error) => Some(error),
+            HeatwaveError::Render(error) => Some(error),
+            HeatwaveError::Compute(error) => Some(error),
+            HeatwaveError::BufferWrite(error) => Some(error),
+            HeatwaveError::Uniform(error) => Some(error),
+            HeatwaveError::Heap(error) => Some(error),
+            HeatwaveError::Gpu(error) => Some(error),
+            HeatwaveError::Texture(error) => Some(error),
+            HeatwaveError::Atlas(error) => Some(error),
+            HeatwaveError::Preprocess(error) => Some(error),
+            HeatwaveError::Reflection(error) => Some(error),
+            HeatwaveError::VertexLayout(error) => Some(error),
+            HeatwaveError::EventLoop(error) => Some(error),
+        }
+    }
+}
+impl From<HeatwaveInitialiseError> for HeatwaveError {
+    fn from(value: HeatwaveInitialiseError) -> Self {
+        HeatwaveError::Initialise(value)
+    }
+}
+impl From<FrameError> for HeatwaveError {
+    fn from(value: FrameError) -> Self {
+        HeatwaveError::Frame(value)
+    }
+}
+impl From<DeviceLostError> for HeatwaveError {
+    fn from(value: DeviceLostError) -> Self {
+        HeatwaveError::DeviceLost(value)
+    }
+}
+impl From<ShaderError> for HeatwaveError {
+    fn from(value: ShaderError) -> Self {
+        HeatwaveError::Shader(value)
+    }
+}
+impl From<ShaderFileError> for HeatwaveError {
+    fn from(value: ShaderFileError) -> Self {
+        HeatwaveError::ShaderFile(value)
+    }
+}
+impl From<PipelineError> for HeatwaveError {
+    fn from(value: PipelineError) -> Self {
+        HeatwaveError::Pipeline(value)
+    }
+}
+impl From<BindGroupError> for HeatwaveError {
+    fn from(value: BindGroupError) -> Self {
+        HeatwaveError::BindGroup(value)
+    }
+}
+impl From<RenderError> for HeatwaveError {
+    fn from(value: RenderError) -> Self {
+        HeatwaveError::Render(value)
+    }
+}
+impl From<ComputeError> for HeatwaveError {
+    fn from(value: ComputeError) -> Self {
+        HeatwaveError::Compute(value)
+    }
+}
+impl From<BufferWriteError> for HeatwaveError {
+    fn from(value: BufferWriteError) -> Self {
+        HeatwaveError::BufferWrite(value)
+    }
+}
+impl From<UniformRingError> for HeatwaveError {
+    fn from(value: UniformRingError) -> Self {
+        HeatwaveError::Uniform(value)
+    }
+}
+impl From<HeapError> for HeatwaveError {
+    fn from(value: HeapError) -> Self {
+        HeatwaveError::Heap(value)
+    }
+}
+impl From<GpuError> for HeatwaveError {
+    fn from(value: GpuError) -> Self {
+        HeatwaveError::Gpu(value)
+    }
+}
+impl From<TextureError> for HeatwaveError {
+    fn from(value: TextureError) -> Self {
+        HeatwaveError::Texture(value)
+    }
+}
+impl From<AtlasError> for HeatwaveError {
+    fn from(value: AtlasError) -> Self {
+        HeatwaveError::Atlas(value)
+    }
+}
+impl From<PreprocessError> for HeatwaveError {
+    fn from(value: PreprocessError) -> Self {
+        HeatwaveError::Preprocess(value)
+    }
+}
+impl From<ReflectionError> for HeatwaveError {
+    fn from(value: ReflectionError) -> Self {
+        HeatwaveError::Reflection(value)
+    }
+}
+impl From<VertexLayoutError> for HeatwaveError {
+    fn from(value: VertexLayoutError) -> Self {
+        HeatwaveError::VertexLayout(value)
+    }
+}
+impl From<EventLoopError> for HeatwaveError {
+    fn from(value: EventLoopError) -> Self {
+        HeatwaveError::EventLoop(value)
+    }
+}
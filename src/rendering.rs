@@ -1,11 +1,27 @@
-mod shader_objects;
+///Premade [`ShaderObject`]s for common vertex formats
+pub mod shader_objects;
+mod atlas;
+mod mipmaps;
+mod preprocessor;
+mod reflection;
 
-use std::{collections::HashMap, ops::Deref, path::PathBuf};
+use std::{collections::HashSet, error::Error, fmt::Display, ops::Range, path::{Path, PathBuf}, sync::Arc};
 
-use wgpu::{naga::back::msl::sampler, Device, RenderPipelineDescriptor, ShaderModule, Surface, SurfaceTexture, VertexBufferLayout};
+use log::warn;
+use wgpu::{Device, Queue, RenderPipelineDescriptor, ShaderModule, Surface, SurfaceTexture, VertexBufferLayout};
 use winit::{dpi::{PhysicalPosition, PhysicalSize}, event::{AxisId, DeviceId, ElementState, Ime, InnerSizeWriter, KeyEvent, Modifiers, MouseButton, MouseScrollDelta, Touch, TouchPhase}};
 
-use crate::{gpu::GpuConnection, HeatwaveApp};
+use crate::{
+	gpu::{submit_dispatch, write_buffer, BufferHandle, BufferWriteError, ComputeDispatch, ComputeError, ComputeReadback, FrameError, GpuError, RawBufferHandle, Registry, RenderPipelineHandle, ShaderFileError, ShaderHandle, UniformAllocation,
+		UniformRing, UniformRingError
+	},
+	DeviceLostError, HeatwaveApp, PipelineError
+};
+
+pub(crate) use mipmaps::MipmapGenerator;
+pub use atlas::{AtlasConfig, AtlasEntry, AtlasError, AtlasPacker, AtlasRect, TextureAtlas, UvRect};
+pub use preprocessor::{DirectiveError, PreprocessError, PreprocessedShader, ShaderPreprocessor, SourceLocation};
+pub use reflection::{EntryPoint, ReflectedBinding, ReflectedLayout, ReflectionError, ShaderReflection, VertexInput, VertexInputType, VertexLayoutError, VertexScalar};
 
 ///A user defined handler of window events. Almost all events are optional functions.
 ///Please see the [documentation](`Presenter`) to see what events are available.
@@ -15,49 +31,64 @@ pub trait Presenter {
 	///Called whenever the app wants to close. After this finished execution, the window will be killed.
 	fn on_exit(&mut self) {}
 	///Called whenever the mouse cursor enters the bounds of the window.
-	fn on_cursor_enter(&mut self, device_id: DeviceId) {}
+	fn on_cursor_enter(&mut self, _device_id: DeviceId) {}
 	///Called whenever the mouse cursor moves within the bounds of the window.
-	fn on_cursor_move(&mut self, device_id: DeviceId, position: PhysicalPosition<f64>) {}
+	fn on_cursor_move(&mut self, _device_id: DeviceId, _position: PhysicalPosition<f64>) {}
 	///Called whenever the mouse cursor leaves the bounds of the window.
-	fn on_cursor_leave(&mut self, device_id: DeviceId) {}
+	fn on_cursor_leave(&mut self, _device_id: DeviceId) {}
 	///Requests the presenter to make draw calls to the GPU as to provide the next frame of the app.
-	fn render(data: Self::RenderData, helper: Option<RenderHelper>);
+	///
+	///`helper` is an error if the frame couldn't be acquired. Most errors mean the frame is skipped, but if the error [is fatal](`FrameError::is_fatal`), the app closes once this returns.
+	fn render(data: Self::RenderData, helper: Result<RenderHelper, FrameError>);
 	///Pack the data you require into the RenderData.
 	fn package_render_data(&mut self) -> Self::RenderData;
 	///Called whenever the window's client region is resized
-	fn on_window_resize(&mut self, new_size: PhysicalSize<u32>) {}
+	fn on_window_resize(&mut self, _new_size: PhysicalSize<u32>) {}
 	///Called whenever the window's position changes
-	fn on_window_move(&mut self, new_position: PhysicalPosition<i32>) {}
+	fn on_window_move(&mut self, _new_position: PhysicalPosition<i32>) {}
 	///Called whenever the user drops a file on the window. Calls multiple times for multiple files 
-	fn on_file_drop(&mut self, path: PathBuf) {}
+	fn on_file_drop(&mut self, _path: PathBuf) {}
 	///Called whenever the user hovers one or more files over the window. Calls multiple times for multiple files
-	fn on_file_hover(&mut self, path: PathBuf) {}
+	fn on_file_hover(&mut self, _path: PathBuf) {}
 	///Called whenever the user cancels a file hover over the window. Calls once regardless of file count
 	fn on_file_hover_cancel(&mut self) {}
 	///Called whenever the window is focused or unfocused
-	fn on_window_focus_changed(&mut self, focused: bool) {}
+	fn on_window_focus_changed(&mut self, _focused: bool) {}
 	///Called whenever a key on the keyboard is pressed
-	fn on_key_press(&mut self, args: KeyPressEventArgs) {}
+	fn on_key_press(&mut self, _args: KeyPressEventArgs) {}
 	///Called whenever a key on the keyboard is released
-	fn on_key_release(&mut self, args: KeyPressEventArgs) {}
+	fn on_key_release(&mut self, _args: KeyPressEventArgs) {}
 	///Called whenever the modifiers held on the keyboard change (Shift, Ctrl, etc.)
-	fn on_modifier_changed(&mut self, modifiers: Modifiers) {}
+	fn on_modifier_changed(&mut self, _modifiers: Modifiers) {}
 	///Called whenever the user starts typing a character via Ime (multi-keypress characters)
-	fn on_ime_input(&mut self, ime: Ime) {}
+	fn on_ime_input(&mut self, _ime: Ime) {}
 	///Called whenever the scroll wheel moves
-	fn on_mouse_scroll(&mut self, args: MouseScrollEventArgs) {}
+	fn on_mouse_scroll(&mut self, _args: MouseScrollEventArgs) {}
 	///Called whenever the user presses a mouse button
-	fn on_mouse_down(&mut self, args: MousePressEventArgs) {}
+	fn on_mouse_down(&mut self, _args: MousePressEventArgs) {}
 	///Called whenever the user releases a mouse button
-	fn on_mouse_up(&mut self, args: MousePressEventArgs) {}
+	fn on_mouse_up(&mut self, _args: MousePressEventArgs) {}
 	///Called whenever some analog control device (such as a joystick or gamepad) changes stick position
-	fn on_analog_axis_motion(&mut self, args: AnalogAxisEventArgs) {}
+	fn on_analog_axis_motion(&mut self, _args: AnalogAxisEventArgs) {}
 	///Called whenever the window's scale factor changes (DPI change, scale change etc.)
-	fn on_scale_factor_change(&mut self, new_scale_factor: f64, writer: InnerSizeWriter) {}
+	fn on_scale_factor_change(&mut self, _new_scale_factor: f64, _writer: InnerSizeWriter) {}
 	///Called whenever the window is fully hidden from view
-	fn on_occlusion(&mut self, occluded: bool) {}
+	fn on_occlusion(&mut self, _occluded: bool) {}
 	///Called whenever the window was touched on a touch screen
-	fn on_touch(&mut self, touch: Touch) {}
+	fn on_touch(&mut self, _touch: Touch) {}
+	///Called whenever wgpu reports an error that wasn't captured, such as a validation error in submitted work.\
+	///Without this, wgpu would panic. The error has already been logged
+	fn on_gpu_error(&mut self, _error: GpuError) {}
+	///Called when the GPU device is lost. Unless [`HeatwaveConfig::recover_lost_device`](crate::HeatwaveConfig::recover_lost_device) is set, the app closes after this
+	fn on_device_lost(&mut self, _error: DeviceLostError) {}
+	///Called once the device has been recreated after being lost. Anything made with the old device outside of Heatwave, such as bind groups, needs to be made again.
+	///
+	///See [`HeatwaveApp::recover_device`] for what is recreated automatically
+	fn on_device_recovered(&mut self) {}
+	///Called when a shader loaded with [`HeatwaveApp::load_shader`] has been reloaded after its file changed. Pipelines made with the app's pipeline layout already use the new shader
+	fn on_shader_reloaded(&mut self, _shader: ShaderHandle) {}
+	///Called when a changed shader file couldn't be reloaded, such as when it doesn't compile. The previous shader and pipelines keep being used, and the error has already been logged
+	fn on_shader_error(&mut self, _error: ShaderFileError) {}
 }
 ///Event data relating to keyboard inputs
 pub struct KeyPressEventArgs {
@@ -102,73 +133,329 @@ pub struct AnalogAxisEventArgs {
 ///
 ///The functions provided by RenderHelper are there to simplify rendering, and they do not provide any real advantage over using wpgu rendering manually.
 pub struct RenderHelper<'a> {
-	pub surface: &'a Surface<'a>,
-	pub texture: SurfaceTexture,
+	///The surface being drawn to. `None` when the app is headless
+	pub surface: Option<&'a Surface<'a>>,
+	pub texture: FrameTexture<'a>,
 	pub depth_texture: &'a Texture,
+	///The multisampled texture drawn to, which is resolved into the frame's texture. `None` when MSAA is off
+	pub msaa_texture: Option<&'a Texture>,
 	pub device: &'a Device,
+	pub queue: &'a Queue,
+
+	pub buffers: &'a Registry<wgpu::Buffer>,
+	pub render_pipelines: &'a Registry<wgpu::RenderPipeline>,
+	pub compute_pipelines: &'a Registry<wgpu::ComputePipeline>,
+	///The render pipelines made with the app's pipeline layout. The bound groups are only set for draws using these
+	pub app_layout_pipelines: &'a HashSet<RenderPipelineHandle>,
 
-	pub render_pipelines: &'a HashMap<usize, wgpu::RenderPipeline>,
-	pub compute_pipelines: &'a HashMap<usize, wgpu::ComputePipeline>,
+	pub bind_groups: &'a Registry<wgpu::BindGroup>,
+	///The bind group bound at each group index, set for every draw using the app's pipeline layout. See [`HeatwaveApp::set_bind_group`]
+	pub bound_groups: Vec<Option<&'a wgpu::BindGroup>>,
+	uniform_ring: Option<&'a UniformRing>,
+	///The same device as `device`, shared so readbacks can poll it from another thread
+	shared_device: &'a Arc<Device>,
 
 	pub background: wgpu::Color
 }
 impl<'a> RenderHelper<'a> {
-	pub fn new(window: &'a HeatwaveApp) -> Self {
-		RenderHelper {
+	///Acquires the next frame of the app, and provides the components needed to draw it.
+	///
+	///# Errors
+	/// Errors if the frame can't be drawn. See [`GpuConnection::acquire_frame`]
+	pub fn new(window: &'a HeatwaveApp) -> Result<Self, FrameError> {
+		let texture = window.connection().acquire_frame()?;
+		Ok(RenderHelper {
 			surface: window.connection().surface(),
-			texture: window.connection().surface().get_current_texture().expect("Texture reference already exists that references this connection's surface"),
+			texture,
 			device: window.connection().device(),
+			queue: window.connection().queue(),
+			buffers: &window.buffers,
 			render_pipelines: &window.render_pipelines,
 			compute_pipelines: &window.compute_pipelines,
+			app_layout_pipelines: &window.app_layout_pipelines,
+			bind_groups: &window.bind_groups,
+			bound_groups: window.bound_groups.iter().map(|handle| handle.and_then(|handle| window.bind_groups.get(handle))).collect(),
+			uniform_ring: window.uniform_ring.as_ref(),
+			shared_device: window.connection().shared_device(),
 			background: window.skybox,
-			depth_texture: window.connection().depth_texture()
-		}
+			depth_texture: window.connection().depth_texture(),
+			msaa_texture: window.connection().msaa_texture()
+		})
 	}
 
-	///Runs the specified compute pipelines on the GPU
+	///Writes the value to the start of the buffer. The write happens before the frame is drawn.
+	///
+	///The buffer must have been created with [`wgpu::BufferUsages::COPY_DST`]
+	///
+	///# Errors
+	/// Errors if the buffer doesn't exist, can't be written to, is too small, or if the size of `T` isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]
+	pub fn write_buffer<T: bytemuck::Pod>(&self, buffer: BufferHandle<T>, value: &T) -> Result<(), BufferWriteError> {
+		self.write_buffer_slice(buffer, 0, std::slice::from_ref(value))
+	}
+	///Writes the values to the buffer, starting at the element `first_element`. The write happens before the frame is drawn.
+	///
+	///The buffer must have been created with [`wgpu::BufferUsages::COPY_DST`]
+	///
+	///# Errors
+	/// Errors if the buffer doesn't exist, can't be written to, or if the values would go past the end of it.\
+	/// Also errors if the byte offset or size of the write isn't a multiple of [`wgpu::COPY_BUFFER_ALIGNMENT`]
+	pub fn write_buffer_slice<T: bytemuck::Pod>(&self, buffer: BufferHandle<T>, first_element: usize, values: &[T]) -> Result<(), BufferWriteError> {
+		write_buffer(self.queue, self.buffers, buffer.raw(), first_element, std::mem::size_of::<T>() as wgpu::BufferAddress, bytemuck::cast_slice(values))
+	}
+
+	///Copies the value into the app's uniform ring, returning where it was put. Set the allocation on [`DrawCall::uniforms`] to bind it for a draw.
+	///
+	///The allocation is only valid for this frame.
 	///
-	///Todo: Figure out how to return the value to the user
-	pub fn compute_with_pipelines(&self, ids: &[&usize]) {
-		
+	///# Errors
+	/// Errors if [`HeatwaveConfig::uniform_ring`](crate::HeatwaveConfig::uniform_ring) isn't set, or if `T` is bigger than the ring's binding size
+	pub fn allocate_uniform<T: bytemuck::Pod>(&self, value: &T) -> Result<UniformAllocation, UniformRingError> {
+		self.uniform_ring.ok_or(UniformRingError::Disabled)?.allocate(self.device, self.queue, bytemuck::bytes_of(value))
 	}
-	///Runs all compute pipelines on the GPU
+
+	///Runs the compute dispatch on the GPU, without reading anything back
+	///
+	///# Errors
+	/// Errors if the dispatch references a pipeline that doesn't exist
+	pub fn dispatch(&self, dispatch: &ComputeDispatch) -> Result<(), ComputeError> {
+		submit_dispatch(self.device, self.queue, self.compute_pipelines, self.buffers, dispatch, None).map(|_| ())
+	}
+	///Runs every compute pipeline on the GPU once, with a single workgroup and no bind groups
+	#[deprecated(note = "Pipelines usually need bind groups and a workgroup count. Use `dispatch` with a `ComputeDispatch` for each pipeline instead")]
 	pub fn compute_all_pipelines(&self) {
-		self.compute_with_pipelines(self.compute_pipelines.into_keys().collect::<Vec<_>>().deref())
+		for (handle, _) in self.compute_pipelines.iter() {
+			self.dispatch(&ComputeDispatch::new(handle, [1, 1, 1])).expect("Expected every registered compute pipeline to exist");
+		}
+	}
+	///Runs the compute dispatch on the GPU, then reads the contents of the output buffer back as a list of `T`.\
+	///Blocks until the results are available. See [`RenderHelper::compute_async`] for the non-blocking version.
+	///
+	///The output buffer must have been created with [`wgpu::BufferUsages::COPY_SRC`]
+	///
+	///# Errors
+	/// Errors if the dispatch references a pipeline or buffer that doesn't exist, if the output buffer can't be copied from, or if the results couldn't be mapped
+	pub fn compute<T: bytemuck::Pod>(&self, dispatch: &ComputeDispatch, output: BufferHandle<T>) -> Result<Vec<T>, ComputeError> {
+		self.compute_async(dispatch, output)?.block()
 	}
-	///Runs the specified rendering pipelines on the GPU
-	///Loads the skybox in the 
-	pub fn render_with_pipelines(&self, ids: &[&usize]) {
-		let view = self.texture.texture.create_view(&wgpu::TextureViewDescriptor::default());
+	///Runs the compute dispatch on the GPU, returning a future that resolves to the contents of the output buffer as a list of `T`.\
+	///On native platforms, a helper thread waits on the device while the future is pending, so polling it doesn't spin the executor.
+	///
+	///The output buffer must have been created with [`wgpu::BufferUsages::COPY_SRC`]
+	///
+	///# Errors
+	/// Errors if the dispatch references a pipeline or buffer that doesn't exist, or if the output buffer can't be copied from.\
+	/// The future errors if the results couldn't be mapped
+	pub fn compute_async<T: bytemuck::Pod>(&self, dispatch: &ComputeDispatch, output: BufferHandle<T>) -> Result<ComputeReadback<'a, T>, ComputeError> {
+		let staging = submit_dispatch(self.device, self.queue, self.compute_pipelines, self.buffers, dispatch, Some(output.raw()))?.expect("Expected a staging buffer for a dispatch with an output");
+		Ok(ComputeReadback::new(self.shared_device, staging))
+	}
+	///Draws the frame using the draw calls provided, in order, then submits the work to the GPU and presents the frame.
+	///
+	///The frame is cleared to the background colour before drawing, so an empty slice of draw calls presents just the background.
+	///
+	///Draws using the app's pipeline layout have the app's bound groups set first, which the draw's own bind groups replace.
+	///
+	///When MSAA is on, the frame is drawn to the multisampled texture and resolved into the frame's texture at the end of the pass.
+	///
+	///# Errors
+	/// Errors if a draw call references a pipeline or buffer that doesn't exist, or reads a buffer from past its end. Nothing is drawn or presented if this happens.
+	pub fn render_with_pipelines(self, draws: &[DrawCall]) -> Result<(), RenderError> {
+		for draw in draws {
+			if !self.render_pipelines.contains(draw.pipeline) {
+				return Err(RenderError::UnknownPipeline(draw.pipeline));
+			}
+			if let Some(&handle) = draw.vertex_buffers.iter().chain(draw.index_buffer.as_ref().map(|(handle, _)| handle)).find(|&&handle| !self.buffers.contains(handle)) {
+				return Err(RenderError::UnknownBuffer(handle));
+			}
+			let mut offsets = draw.vertex_buffers.iter().zip(draw.vertex_offsets).chain(draw.index_buffer.as_ref().map(|(handle, _)| (handle, &draw.index_offset)));
+			if let Some((&handle, _)) = offsets.find(|&(&handle, &offset)| offset > self.buffers[handle].size()) {
+				return Err(RenderError::OffsetOutOfRange(handle));
+			}
+		}
+
+		let view = self.texture.texture().create_view(&wgpu::TextureViewDescriptor::default());
+		let uniform_ring = self.uniform_ring.map(|ring| (ring.group(), ring.lock())); //Held until the pass is finished, as the pass borrows the ring's bind groups
 
 		let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
 			label: Some("Heatwave Rendering Encoder")
 		});
 
-		let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-			label: Some("Heatwave Render Pass"),
-			color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-				view: &view,
-				resolve_target: None,
-				ops: wgpu::Operations {
-					load: wgpu::LoadOp::Clear(self.background),
-					store: wgpu::StoreOp::Store
-				}
-			})],
-			depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
-				view: &self.depth_texture.view,
-				depth_ops: Some(wgpu::Operations {
-					load: wgpu::LoadOp::Clear(1.0),
-					store: wgpu::StoreOp::Store
+		{
+			let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: Some("Heatwave Render Pass"),
+				color_attachments: &[Some(match self.msaa_texture {
+					Some(msaa_texture) => wgpu::RenderPassColorAttachment {
+						view: &msaa_texture.view,
+						resolve_target: Some(&view),
+						ops: wgpu::Operations {
+							load: wgpu::LoadOp::Clear(self.background),
+							store: wgpu::StoreOp::Discard //Only the resolved frame is kept
+						}
+					},
+					None => wgpu::RenderPassColorAttachment {
+						view: &view,
+						resolve_target: None,
+						ops: wgpu::Operations {
+							load: wgpu::LoadOp::Clear(self.background),
+							store: wgpu::StoreOp::Store
+						}
+					}
+				})],
+				depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+					view: &self.depth_texture.view,
+					depth_ops: Some(wgpu::Operations {
+						load: wgpu::LoadOp::Clear(1.0),
+						store: wgpu::StoreOp::Store
+					}),
+					stencil_ops: None
 				}),
-				stencil_ops: None
-			}),
-			timestamp_writes: None,
-			occlusion_query_set: None
-		});
+				timestamp_writes: None,
+				occlusion_query_set: None
+			});
+
+			for draw in draws {
+				render_pass.set_pipeline(&self.render_pipelines[draw.pipeline]);
+				let app_layout = self.app_layout_pipelines.contains(&draw.pipeline);
+				if app_layout {
+					for (index, bind_group) in self.bound_groups.iter().enumerate() {
+						if let Some(bind_group) = bind_group {
+							render_pass.set_bind_group(index as u32, bind_group, &[]);
+						}
+					}
+				}
+				for (index, bind_group) in draw.bind_groups.iter().enumerate() {
+					render_pass.set_bind_group(index as u32, bind_group, &[]);
+				}
+				//The ring's group index only exists in the app's pipeline layout
+				if let (true, Some(allocation), Some((group, ring))) = (app_layout, draw.uniforms, &uniform_ring) {
+					if let Some(bind_group) = ring.bind_group(allocation) {
+						render_pass.set_bind_group(*group, bind_group, &[allocation.offset()]);
+					}
+				}
+				for (slot, &handle) in draw.vertex_buffers.iter().enumerate() {
+					let offset = draw.vertex_offsets.get(slot).copied().unwrap_or(0);
+					render_pass.set_vertex_buffer(slot as u32, self.buffers[handle].slice(offset..));
+				}
+
+				match draw.index_buffer {
+					Some((handle, format)) => {
+						render_pass.set_index_buffer(self.buffers[handle].slice(draw.index_offset..), format);
+						render_pass.draw_indexed(draw.elements.clone(), draw.base_vertex, draw.instances.clone());
+					}
+					None => render_pass.draw(draw.elements.clone(), draw.instances.clone())
+				}
+			}
+		} //The render pass borrows the encoder, so has to be dropped before the encoder can finish
+
+		self.queue.submit(Some(encoder.finish()));
+		self.texture.present();
+
+		Ok(())
+	}
+	///Presents the frame cleared to the background colour.
+	///
+	///Pipelines can't be drawn without their buffers and element ranges, so nothing is drawn. See [`RenderHelper::render_with_pipelines`]
+	#[deprecated(note = "Draws nothing, as pipelines need draw calls. Use `render_with_pipelines` with a `DrawCall` for each pipeline instead")]
+	pub fn render_all_pipelines(self) {
+		self.render_with_pipelines(&[]).expect("Expected a frame without draw calls to render");
+	}
+}
+
+///Describes a single draw made by [`RenderHelper::render_with_pipelines`]
+///
+///# Usage
+///```rs
+/// let draw = DrawCall {
+///     vertex_buffers: &[my_vertex_buffer.raw()],
+///     index_buffer: Some((my_index_buffer.raw(), wgpu::IndexFormat::Uint16)),
+///     ..DrawCall::new(my_pipeline, 0..index_count)
+/// };
+///
+/// helper.render_with_pipelines(&[draw])?;
+///```
+#[derive(Clone, Debug)]
+pub struct DrawCall<'a> {
+	///The render pipeline to draw with
+	pub pipeline: RenderPipelineHandle,
+	///The vertex buffers to use. Each buffer is bound to the slot of its position in the slice
+	pub vertex_buffers: &'a [RawBufferHandle],
+	///The byte offset to start reading each vertex buffer from, by slot. Buffers without an offset are read from the start
+	pub vertex_offsets: &'a [wgpu::BufferAddress],
+	///The index buffer to use and its format. The draw is indexed if this is set
+	pub index_buffer: Option<(RawBufferHandle, wgpu::IndexFormat)>,
+	///The byte offset to start reading the index buffer from
+	pub index_offset: wgpu::BufferAddress,
+	///The bind groups to use. Each group is bound to the index of its position in the slice, replacing the app's bound group at that index for this draw
+	pub bind_groups: &'a [&'a wgpu::BindGroup],
+	///The uniform to bind from the app's uniform ring, at the group after the config's bind groups. See [`RenderHelper::allocate_uniform`]\
+	///Only bound for pipelines made with the app's pipeline layout, as custom layouts don't have the ring's group
+	pub uniforms: Option<UniformAllocation>,
+	///The range of vertices to draw, or the range of indices if the draw is indexed
+	pub elements: Range<u32>,
+	///The range of instances to draw. Use `0..1` when not instancing
+	pub instances: Range<u32>,
+	///The value added to each index before fetching its vertex. Ignored if the draw isn't indexed
+	pub base_vertex: i32
+}
+impl DrawCall<'_> {
+	///Creates a draw call for the pipeline with no buffers or bind groups, drawing a single instance of the elements given
+	pub fn new(pipeline: RenderPipelineHandle, elements: Range<u32>) -> Self {
+		DrawCall {
+			pipeline,
+			vertex_buffers: &[],
+			vertex_offsets: &[],
+			index_buffer: None,
+			index_offset: 0,
+			bind_groups: &[],
+			uniforms: None,
+			elements,
+			instances: 0..1,
+			base_vertex: 0
+		}
 	}
-	///Runs all rendering pipelines on the GPU
-	pub fn render_all_pipelines(&self) {
-		self.render_with_pipelines(self.render_pipelines.into_keys().collect::<Vec<_>>().deref())
+}
+
+///Describes why a [`RenderHelper`] couldn't draw a frame
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RenderError {
+	///The render pipeline doesn't exist, or was removed
+	UnknownPipeline(RenderPipelineHandle),
+	///The buffer doesn't exist, or was removed
+	UnknownBuffer(RawBufferHandle),
+	///The draw's offset into the buffer is past its end
+	OffsetOutOfRange(RawBufferHandle)
+}
+impl Display for RenderError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			RenderError::UnknownPipeline(handle) => write!(f, "No render pipeline exists for {handle:?}"),
+			RenderError::UnknownBuffer(handle) => write!(f, "No buffer exists for {handle:?}"),
+			RenderError::OffsetOutOfRange(handle) => write!(f, "The draw's offset into {handle:?} is past the end of the buffer")
+		}
+	}
+}
+impl Error for RenderError {}
+
+///The texture a frame is being drawn to
+pub enum FrameTexture<'a> {
+	///The next texture of a window's surface
+	Surface(SurfaceTexture),
+	///The offscreen texture of a headless app
+	Offscreen(&'a Texture)
+}
+impl FrameTexture<'_> {
+	///The texture to draw to
+	pub fn texture(&self) -> &wgpu::Texture {
+		match self {
+			FrameTexture::Surface(surface_texture) => &surface_texture.texture,
+			FrameTexture::Offscreen(texture) => &texture.texture
+		}
+	}
+	///Shows the frame on the surface. Does nothing for offscreen textures, as they're never shown
+	pub fn present(self) {
+		if let FrameTexture::Surface(surface_texture) = self {
+			surface_texture.present();
+		}
 	}
 }
 
@@ -191,10 +478,13 @@ pub trait ShaderObject {
 /// - The topology is a triangle list
 /// - The front face is the counter clockwise side
 /// - Culls the back faces
-/// - Depth clipping is enabled, though will be disabled by default if the [`wgpu_types::Features::DEPTH_CLIP_CONTROL`] feature is enabled
-/// - Polygons will rasterise in fill mode, by default.
-/// - Conservative rasterisation is disabled by default, but enabled if [`wgpu_types::Features::CONSERVATIVE_RASTERIZATION`] is enabled (Any pixel touched by the polygon is filled, rather than only if most of the pixel is touched)
+/// - Depth is tested with `Less` and written
+/// - Depth clipping is enabled
+/// - Polygons will rasterise in fill mode
+/// - Conservative rasterisation is disabled
 /// - Assumes only 1 view is needed 
+///
+/// Use [`SimpleRenderPipelineDescriptor::builder`] to change any of these
 ///# Usage
 ///```rs
 /// let descriptor = SimpleRenderPipelineDescriptor {
@@ -206,8 +496,9 @@ pub trait ShaderObject {
 ///     vertex_buffer_format: config.format
 /// }
 ///                          
-/// let my_pipeline_id = my_heatwave_window.add_render_pipeline(descriptor);
+/// let my_pipeline = my_heatwave_window.add_render_pipeline(descriptor);
 ///```
+#[derive(Clone, Copy)]
 pub struct SimpleRenderPipelineDescriptor<'a> {
 	///The name of the pipeline. Used for debugging
 	pub name: &'a str,
@@ -222,12 +513,19 @@ pub struct SimpleRenderPipelineDescriptor<'a> {
 	///The format of any vertex buffers used by the pipeline
 	pub vertex_buffer_format: &'a [VertexBufferLayout<'a>],
 }
+impl<'a> SimpleRenderPipelineDescriptor<'a> {
+	///Starts a [`RenderPipelineBuilder`] from the descriptor, for changing the assumptions it makes
+	pub fn builder(self) -> RenderPipelineBuilder<'a> {
+		RenderPipelineBuilder::new(self)
+	}
+}
 impl<'a> From<SimpleRenderPipelineDescriptor<'a>> for RenderPipelineDescriptor<'a> {
 	///Converts a SimpleRenderPipelineDescriptor into a RenderPipelineDescriptor.
 	/// 
 	/// It is missing the following information:\
 	/// * layout (Needs pipeline layout from the heatwave window)
 	/// * color states of the fragment shader's render targets (Needs formats from the surfaces)
+	/// * multisample count (Needs the sample count of the heatwave window's frames)
 	/// 
 	/// This information is automatically filled in when adding it to a HeatwaveWindow
     fn from(val: SimpleRenderPipelineDescriptor<'a>) -> Self {
@@ -261,7 +559,7 @@ impl<'a> From<SimpleRenderPipelineDescriptor<'a>> for RenderPipelineDescriptor<'
 				bias: wgpu::DepthBiasState::default()
 			}),
 			multisample: wgpu::MultisampleState {
-				count: 8,
+				count: 1,
 				mask: !0,
 				alpha_to_coverage_enabled: false
 			},
@@ -270,6 +568,194 @@ impl<'a> From<SimpleRenderPipelineDescriptor<'a>> for RenderPipelineDescriptor<'
     }
 }
 
+///Builds a render pipeline from a [`SimpleRenderPipelineDescriptor`], changing the assumptions it makes about blending, primitives and depth.
+///
+///Settings that need a device feature fall back to their default, with a warning, if the feature isn't enabled.\
+///Use [`GpuConnection::has_features`](crate::gpu::GpuConnection::has_features) to check for them first.
+///
+///# Usage
+///```rs
+/// let my_pipeline = SimpleRenderPipelineDescriptor {
+///     name: "Particles",
+///     vertex: &my_shader_module,
+///     fragment: Some(&my_shader_module),
+///     vertex_entry_point: "vs_main",
+///     fragment_entry_point: Some("fs_main"),
+///     vertex_buffer_format: &[]
+/// }.builder()
+///     .blend(BlendPreset::Additive)
+///     .cull_mode(None)
+///     .depth_write(false)
+///     .build(&mut my_heatwave_app)?;
+///```
+#[derive(Clone, Copy)]
+pub struct RenderPipelineBuilder<'a> {
+	descriptor: SimpleRenderPipelineDescriptor<'a>,
+	blend: BlendPreset,
+	primitive: wgpu::PrimitiveState,
+	depth_write: bool,
+	depth_compare: wgpu::CompareFunction,
+	depth_bias: wgpu::DepthBiasState
+}
+impl<'a> RenderPipelineBuilder<'a> {
+	///Starts with the assumptions of the descriptor
+	pub fn new(descriptor: SimpleRenderPipelineDescriptor<'a>) -> Self {
+		let defaults: RenderPipelineDescriptor = descriptor.into();
+		let depth = defaults.depth_stencil.expect("Expected a simple pipeline to have a depth stencil state");
+		RenderPipelineBuilder {
+			descriptor,
+			blend: BlendPreset::Replace,
+			primitive: defaults.primitive,
+			depth_write: depth.depth_write_enabled,
+			depth_compare: depth.depth_compare,
+			depth_bias: depth.bias
+		}
+	}
+
+	///How the fragment shader's output is mixed with the pixels already drawn.
+	///
+	///Defaults to `Replace`
+	pub fn blend(mut self, blend: BlendPreset) -> Self {
+		self.blend = blend;
+		self
+	}
+	///How vertices are joined into primitives. Strip topologies join each vertex to the ones before it.
+	///
+	///Defaults to `TriangleList`
+	pub fn topology(mut self, topology: wgpu::PrimitiveTopology) -> Self {
+		self.primitive.topology = topology;
+		self
+	}
+	///Which winding order is the front of a triangle.
+	///
+	///Defaults to `Ccw`
+	pub fn front_face(mut self, front_face: wgpu::FrontFace) -> Self {
+		self.primitive.front_face = front_face;
+		self
+	}
+	///Which face of a triangle is skipped, or `None` to draw both.
+	///
+	///Defaults to `Some(Face::Back)`
+	pub fn cull_mode(mut self, cull_mode: Option<wgpu::Face>) -> Self {
+		self.primitive.cull_mode = cull_mode;
+		self
+	}
+	///How triangles are filled. `Line` needs [`wgpu::Features::POLYGON_MODE_LINE`], and `Point` needs [`wgpu::Features::POLYGON_MODE_POINT`].
+	///
+	///Defaults to `Fill`
+	pub fn polygon_mode(mut self, polygon_mode: wgpu::PolygonMode) -> Self {
+		self.primitive.polygon_mode = polygon_mode;
+		self
+	}
+	///Whether depths outside of 0 to 1 are clamped rather than clipped. Needs [`wgpu::Features::DEPTH_CLIP_CONTROL`].
+	///
+	///Defaults to `false`
+	pub fn unclipped_depth(mut self, unclipped_depth: bool) -> Self {
+		self.primitive.unclipped_depth = unclipped_depth;
+		self
+	}
+	///Whether every pixel a triangle touches is filled, rather than only those whose centre it covers. Needs [`wgpu::Features::CONSERVATIVE_RASTERIZATION`], and only works with the `Fill` polygon mode.
+	///
+	///Defaults to `false`
+	pub fn conservative(mut self, conservative: bool) -> Self {
+		self.primitive.conservative = conservative;
+		self
+	}
+	///Whether the depth of drawn pixels is written to the depth texture. Usually turned off for transparent objects.
+	///
+	///Defaults to `true`
+	pub fn depth_write(mut self, depth_write: bool) -> Self {
+		self.depth_write = depth_write;
+		self
+	}
+	///How a pixel's depth is compared to the depth already drawn, to decide if the pixel is drawn. `Always` turns depth testing off.
+	///
+	///Defaults to `Less`
+	pub fn depth_compare(mut self, depth_compare: wgpu::CompareFunction) -> Self {
+		self.depth_compare = depth_compare;
+		self
+	}
+	///The offset added to the depth of each pixel, such as to stop decals and shadows fighting with the surface they're on. Only triangles are biased.
+	///
+	///Defaults to no bias
+	pub fn depth_bias(mut self, depth_bias: wgpu::DepthBiasState) -> Self {
+		self.depth_bias = depth_bias;
+		self
+	}
+
+	///Adds the pipeline to the app with [`HeatwaveApp::add_render_pipeline`], after dropping any settings whose features aren't enabled on the device
+	///
+	///# Errors
+	/// Errors if wgpu rejects the pipeline, such as when the shader's inputs don't match the vertex buffers
+	pub fn build(self, app: &mut HeatwaveApp) -> Result<RenderPipelineHandle, PipelineError> {
+		let features = app.connection().features();
+		let mut primitive = self.primitive;
+		let (required, feature_name) = match primitive.polygon_mode {
+			wgpu::PolygonMode::Fill => (wgpu::Features::empty(), ""),
+			wgpu::PolygonMode::Line => (wgpu::Features::POLYGON_MODE_LINE, "POLYGON_MODE_LINE"),
+			wgpu::PolygonMode::Point => (wgpu::Features::POLYGON_MODE_POINT, "POLYGON_MODE_POINT")
+		};
+		if !features.contains(required) {
+			warn!("Pipeline \"{}\" uses the {:?} polygon mode, but {feature_name} isn't enabled. Filling polygons instead", self.descriptor.name, primitive.polygon_mode);
+			primitive.polygon_mode = wgpu::PolygonMode::Fill;
+		}
+		if primitive.unclipped_depth && !features.contains(wgpu::Features::DEPTH_CLIP_CONTROL) {
+			warn!("Pipeline \"{}\" uses unclipped depth, but DEPTH_CLIP_CONTROL isn't enabled. Clipping depth instead", self.descriptor.name);
+			primitive.unclipped_depth = false;
+		}
+		if primitive.conservative && !features.contains(wgpu::Features::CONSERVATIVE_RASTERIZATION) {
+			warn!("Pipeline \"{}\" uses conservative rasterisation, but CONSERVATIVE_RASTERIZATION isn't enabled. Rasterising normally instead", self.descriptor.name);
+			primitive.conservative = false;
+		}
+
+		let mut descriptor: RenderPipelineDescriptor = self.descriptor.into();
+		descriptor.primitive = primitive;
+		descriptor.depth_stencil = Some(wgpu::DepthStencilState {
+			format: Texture::DEPTH_FORMAT,
+			depth_write_enabled: self.depth_write,
+			depth_compare: self.depth_compare,
+			stencil: wgpu::StencilState::default(),
+			bias: self.depth_bias
+		});
+		app.add_render_pipeline_with_blend(descriptor, self.blend.state())
+	}
+}
+
+///Common ways to mix a fragment shader's output with the pixels already drawn
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BlendPreset {
+	///Overwrites the pixel
+	Replace,
+	///Mixes with the pixel by the output's alpha, for ordinary transparency
+	Alpha,
+	///Adds the output, scaled by its alpha, to the pixel. For light, fire and other glowing effects
+	Additive,
+	///Mixes with the pixel for outputs whose colour has already been multiplied by their alpha
+	Premultiplied
+}
+impl BlendPreset {
+	///The blend state for the preset
+	pub fn state(&self) -> wgpu::BlendState {
+		match self {
+			BlendPreset::Replace => wgpu::BlendState::REPLACE,
+			BlendPreset::Alpha => wgpu::BlendState::ALPHA_BLENDING,
+			BlendPreset::Additive => wgpu::BlendState {
+				color: wgpu::BlendComponent {
+					src_factor: wgpu::BlendFactor::SrcAlpha,
+					dst_factor: wgpu::BlendFactor::One,
+					operation: wgpu::BlendOperation::Add
+				},
+				alpha: wgpu::BlendComponent {
+					src_factor: wgpu::BlendFactor::One,
+					dst_factor: wgpu::BlendFactor::One,
+					operation: wgpu::BlendOperation::Add
+				}
+			},
+			BlendPreset::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING
+		}
+	}
+}
+
 pub struct Texture {
 	pub texture: wgpu::Texture,
 	pub view: wgpu::TextureView,
@@ -277,31 +763,113 @@ pub struct Texture {
 }
 impl Texture {
 	pub const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+	///The format of the colour texture used by headless connections
+	pub const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8UnormSrgb;
 
-	pub fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, label: &str) -> Self {
+	///Creates a colour texture that can be drawn to in place of a surface, and copied back to the CPU.
+	///
+	///Uses the size and format of the configuration provided.
+	pub fn create_render_target(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, label: &str) -> Self {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(label),
+			size: wgpu::Extent3d {
+				width: config.width,
+				height: config.height,
+				depth_or_array_layers: 1
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: config.format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_SRC,
+			view_formats: &[]
+		});
+
+		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(label),
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			..Default::default()
+		});
+
+		Self {
+			texture,
+			view,
+			sampler
+		}
+	}
+
+	///Creates a colour texture that frames are drawn to before being resolved into a single sampled texture of the same size and format.
+	///
+	///Uses the size and format of the configuration provided. The sample count must be supported by the device for the format
+	pub fn create_multisampled_target(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32, label: &str) -> Self {
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: Some(label),
+			size: wgpu::Extent3d {
+				width: config.width,
+				height: config.height,
+				depth_or_array_layers: 1
+			},
+			mip_level_count: 1,
+			sample_count,
+			dimension: wgpu::TextureDimension::D2,
+			format: config.format,
+			usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+			view_formats: &[]
+		});
+
+		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some(label),
+			..Default::default()
+		}); //Multisampled textures can't be sampled, but every texture has a sampler
+
+		Self {
+			texture,
+			view,
+			sampler
+		}
+	}
+
+	///Creates a depth texture the size of the configuration provided, taking `sample_count` samples per pixel to match the colour texture it's drawn with.
+	///
+	///Only single sampled depth textures can be bound to shaders, as multisampled ones can't be made bindable on every backend.\
+	///The sampler clamps to the border if [`wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER`] is enabled on the device, and to the edge otherwise
+	pub fn create_depth_texture(device: &wgpu::Device, config: &wgpu::SurfaceConfiguration, sample_count: u32, label: &str) -> Self {
 		let size = wgpu::Extent3d {
 			width: config.width,
 			height: config.height,
 			depth_or_array_layers: 1
 		};
+		let mut usage = wgpu::TextureUsages::RENDER_ATTACHMENT;
+		if sample_count == 1 {
+			usage |= wgpu::TextureUsages::TEXTURE_BINDING;
+		}
 		let desc = wgpu::TextureDescriptor {
 			label: Some(label),
 			size,
 			mip_level_count: 1,
-			sample_count: 1,
+			sample_count,
 			dimension: wgpu::TextureDimension::D2,
 			format: Self::DEPTH_FORMAT,
-			usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+			usage,
 			view_formats: &[]
 		};
 		let texture = device.create_texture(&desc);
 
 		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		//ClampToBorder needs a feature that many adapters, such as software ones, don't have
+		let address_mode = if device.features().contains(wgpu::Features::ADDRESS_MODE_CLAMP_TO_BORDER) {
+			wgpu::AddressMode::ClampToBorder
+		} else {
+			wgpu::AddressMode::ClampToEdge
+		};
 		let sampler = device.create_sampler(
 			&wgpu::SamplerDescriptor {
-				address_mode_u: wgpu::AddressMode::ClampToBorder,
-				address_mode_v: wgpu::AddressMode::ClampToBorder,
-				address_mode_w: wgpu::AddressMode::ClampToBorder,
+				address_mode_u: address_mode,
+				address_mode_v: address_mode,
+				address_mode_w: address_mode,
 				mag_filter: wgpu::FilterMode::Linear,
 				min_filter: wgpu::FilterMode::Linear,
 				mipmap_filter: wgpu::FilterMode::Nearest,
@@ -318,4 +886,220 @@ impl Texture {
 			sampler
 		}
 	}
+
+	///Creates a texture from an image, converting it to RGBA8 and uploading it. The upload happens before the next work submitted to the GPU.
+	///
+	///If [`TextureOptions::generate_mipmaps`] is set, the rest of the mip chain is rendered from the image on the GPU.\
+	///The pipeline that renders them is made for each call, so prefer [`HeatwaveApp::load_texture`] when loading many textures with mips, as the app keeps it
+	///
+	///# Errors
+	/// Errors if the image is empty, or larger than the device's `max_texture_dimension_2d`
+	pub fn from_image(device: &wgpu::Device, queue: &wgpu::Queue, image: &image::DynamicImage, options: &TextureOptions) -> Result<Self, TextureError> {
+		Self::from_image_with(device, queue, image, options, &mut MipmapGenerator::default())
+	}
+	///Creates a texture from an image, rendering its mips with the generator given. See [`Texture::from_image`]
+	pub(crate) fn from_image_with(
+		device: &wgpu::Device,
+		queue: &wgpu::Queue,
+		image: &image::DynamicImage,
+		options: &TextureOptions,
+		generator: &mut MipmapGenerator
+	) -> Result<Self, TextureError> {
+		let rgba = image.to_rgba8();
+		let (width, height) = rgba.dimensions();
+		let max = device.limits().max_texture_dimension_2d;
+		if width == 0 || height == 0 || width > max || height > max {
+			return Err(TextureError::InvalidSize { width, height, max });
+		}
+
+		let size = wgpu::Extent3d {
+			width,
+			height,
+			depth_or_array_layers: 1
+		};
+		let format = options.colour_space.format();
+		let mip_level_count = if options.generate_mipmaps { mipmaps::mip_level_count(width, height) } else { 1 };
+		let mut usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST;
+		//Both colour spaces' formats can be rendered to and filtered on every device, so the mips are always rendered
+		if mip_level_count > 1 {
+			usage |= wgpu::TextureUsages::RENDER_ATTACHMENT;
+		}
+		let texture = device.create_texture(&wgpu::TextureDescriptor {
+			label: options.label,
+			size,
+			mip_level_count,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format,
+			usage,
+			view_formats: &[]
+		});
+		queue.write_texture(
+			wgpu::ImageCopyTexture {
+				texture: &texture,
+				mip_level: 0,
+				origin: wgpu::Origin3d::ZERO,
+				aspect: wgpu::TextureAspect::All
+			},
+			&rgba,
+			wgpu::ImageDataLayout {
+				offset: 0,
+				bytes_per_row: Some(4 * width),
+				rows_per_image: Some(height)
+			},
+			size
+		);
+		if mip_level_count > 1 {
+			generator.generate(device, queue, &texture);
+		}
+
+		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+			label: options.label,
+			..options.sampler.descriptor()
+		});
+
+		Ok(Self {
+			texture,
+			view,
+			sampler
+		})
+	}
+	///Creates a texture from an encoded image, such as the contents of a PNG file. See [`Texture::from_image`]
+	///
+	///# Errors
+	/// Errors if the image can't be decoded, is empty, or is larger than the device's `max_texture_dimension_2d`
+	pub fn from_bytes(device: &wgpu::Device, queue: &wgpu::Queue, bytes: &[u8], options: &TextureOptions) -> Result<Self, TextureError> {
+		let image = image::load_from_memory(bytes).map_err(TextureError::Image)?;
+		Self::from_image(device, queue, &image, options)
+	}
+	///Creates a texture from an image file, such as a PNG. See [`Texture::from_image`]
+	///
+	///# Errors
+	/// Errors if the file can't be read or decoded, or if the image is empty or larger than the device's `max_texture_dimension_2d`
+	pub fn from_path(device: &wgpu::Device, queue: &wgpu::Queue, path: impl AsRef<Path>, options: &TextureOptions) -> Result<Self, TextureError> {
+		let image = image::open(path).map_err(TextureError::Image)?;
+		Self::from_image(device, queue, &image, options)
+	}
+}
+
+///How a texture made from an image is created
+///
+///# Usage
+///```rs
+/// let options = TextureOptions {
+///     label: Some("Player"),
+///     sampler: SamplerPreset::LinearRepeat,
+///     generate_mipmaps: true,
+///     ..Default::default()
+/// };
+/// let texture = my_heatwave_app.load_texture("player.png", &options)?;
+///```
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TextureOptions<'a> {
+	///The label of the texture and its sampler. Used for debugging, and as the texture's name when added to an app
+	///
+	///Defaults to `None`
+	pub label: Option<&'a str>,
+	///How the colours of the image are stored.
+	///
+	///Defaults to `Srgb`
+	pub colour_space: ColourSpace,
+	///How the texture is sampled.
+	///
+	///Defaults to `LinearRepeat`
+	pub sampler: SamplerPreset,
+	///Whether to make the full mip chain from the image, so the texture doesn't shimmer when drawn smaller than it is. The sampler preset picks how mips are blended.
+	///
+	///Defaults to `false`
+	pub generate_mipmaps: bool
+}
+impl Default for TextureOptions<'_> {
+	fn default() -> Self {
+		TextureOptions {
+			label: None,
+			colour_space: ColourSpace::Srgb,
+			sampler: SamplerPreset::LinearRepeat,
+			generate_mipmaps: false
+		}
+	}
+}
+
+///How the colours of an image are stored
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColourSpace {
+	///The colours are gamma encoded, as in most images made to be looked at, such as albedo maps and sprites. Shaders read them as linear colours
+	Srgb,
+	///The values are stored as they are, as in data such as normal maps, roughness maps and lookup tables
+	Linear
+}
+impl ColourSpace {
+	///The RGBA8 format that stores colours this way
+	pub fn format(&self) -> wgpu::TextureFormat {
+		match self {
+			ColourSpace::Srgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+			ColourSpace::Linear => wgpu::TextureFormat::Rgba8Unorm
+		}
+	}
+}
+
+///Common ways to sample a texture. Use [`HeatwaveApp::add_sampler`] for anything else.
+///
+///Linear presets blend between mip levels, while nearest presets pick the closest one. This only matters for textures with mips
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SamplerPreset {
+	///Smoothly blends between pixels, and stretches the edge pixels outside of the texture
+	LinearClamp,
+	///Smoothly blends between pixels, and tiles the texture
+	LinearRepeat,
+	///Keeps pixels sharp, as in pixel art, and stretches the edge pixels outside of the texture
+	NearestClamp,
+	///Keeps pixels sharp, as in pixel art, and tiles the texture
+	NearestRepeat
+}
+impl SamplerPreset {
+	///The sampler descriptor for the preset, without a label
+	pub fn descriptor(&self) -> wgpu::SamplerDescriptor<'static> {
+		let (filter, address_mode) = match self {
+			SamplerPreset::LinearClamp => (wgpu::FilterMode::Linear, wgpu::AddressMode::ClampToEdge),
+			SamplerPreset::LinearRepeat => (wgpu::FilterMode::Linear, wgpu::AddressMode::Repeat),
+			SamplerPreset::NearestClamp => (wgpu::FilterMode::Nearest, wgpu::AddressMode::ClampToEdge),
+			SamplerPreset::NearestRepeat => (wgpu::FilterMode::Nearest, wgpu::AddressMode::Repeat)
+		};
+		wgpu::SamplerDescriptor {
+			label: None,
+			address_mode_u: address_mode,
+			address_mode_v: address_mode,
+			address_mode_w: address_mode,
+			mag_filter: filter,
+			min_filter: filter,
+			mipmap_filter: filter,
+			..Default::default()
+		}
+	}
+}
+
+///Describes why a texture couldn't be made from an image
+#[derive(Debug)]
+pub enum TextureError {
+	///The image couldn't be read or decoded
+	Image(image::ImageError),
+	///The image is empty, or is larger than the device supports in either dimension
+	InvalidSize { width: u32, height: u32, max: u32 }
+}
+impl Display for TextureError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			TextureError::Image(error) => write!(f, "The image couldn't be loaded: {error}"),
+			TextureError::InvalidSize { width, height, max } => write!(f, "The image is {width}x{height}, but textures must be between 1x1 and {max}x{max}")
+		}
+	}
+}
+impl Error for TextureError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			TextureError::Image(error) => Some(error),
+			_ => None
+		}
+	}
 }
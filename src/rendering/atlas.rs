@@ -0,0 +1,430 @@
+use std::{collections::HashMap, error::Error, fmt::Display, path::Path};
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::{gpu::TextureHandle, HeatwaveApp};
+
+use super::{ColourSpace, SamplerPreset, Texture, TextureError, TextureOptions};
+
+///Packs rectangles into a fixed size area using the skyline bottom-left method. Knows nothing about textures, so it can be used to lay out anything.
+///
+///Rectangles can be packed one at a time as they come, and packing is deterministic, so the same rectangles packed in the same order always land in the same places.
+#[derive(Clone, Debug)]
+pub struct AtlasPacker {
+	width: u32,
+	height: u32,
+	///The top edge of the packed area along the width, as spans sorted by x that cover the whole width
+	skyline: Vec<SkylineSpan>
+}
+#[derive(Clone, Copy, Debug)]
+struct SkylineSpan {
+	x: u32,
+	y: u32,
+	width: u32
+}
+///An area of an atlas in pixels, with the origin at the top left
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct AtlasRect {
+	pub x: u32,
+	pub y: u32,
+	pub width: u32,
+	pub height: u32
+}
+impl AtlasRect {
+	///Returns true if the rectangles share any pixels
+	pub fn overlaps(&self, other: &AtlasRect) -> bool {
+		self.x < other.x + other.width && other.x < self.x + self.width && self.y < other.y + other.height && other.y < self.y + self.height
+	}
+}
+impl AtlasPacker {
+	///Creates a packer for an empty area of the given size
+	pub fn new(width: u32, height: u32) -> Self {
+		AtlasPacker {
+			width,
+			height,
+			skyline: vec![SkylineSpan { x: 0, y: 0, width }]
+		}
+	}
+
+	///Finds room for a rectangle of the given size, as high up as possible and then as far left as possible.\
+	///Returns `None` if there's no room left for it
+	pub fn pack(&mut self, width: u32, height: u32) -> Option<AtlasRect> {
+		if width == 0 || height == 0 {
+			return None;
+		}
+
+		let (index, y) = (0..self.skyline.len())
+			.filter_map(|index| self.fit(index, width, height).map(|y| (index, y)))
+			.min_by_key(|&(_, y)| y)?; //The first of equally high spots is the leftmost, as spans are sorted by x
+		let x = self.skyline[index].x;
+
+		self.skyline.insert(index, SkylineSpan { x, y: y + height, width });
+		//Trims the spans now under the new one
+		let right = x + width;
+		let next = index + 1;
+		while next < self.skyline.len() && self.skyline[next].x < right {
+			let span = &mut self.skyline[next];
+			let covered = right - span.x;
+			if covered < span.width {
+				span.x += covered;
+				span.width -= covered;
+				break;
+			}
+			self.skyline.remove(next);
+		}
+		self.merge();
+
+		Some(AtlasRect { x, y, width, height })
+	}
+
+	///The width of the area packed into
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+	///The height of the area packed into
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	///The y a rectangle would sit at if its left edge was at the start of the span, or `None` if it wouldn't fit there
+	fn fit(&self, index: usize, width: u32, height: u32) -> Option<u32> {
+		let x = self.skyline[index].x;
+		if x + width > self.width {
+			return None;
+		}
+
+		let mut y = 0;
+		let mut remaining = width;
+		for span in &self.skyline[index..] {
+			if remaining == 0 {
+				break;
+			}
+			y = y.max(span.y);
+			remaining = remaining.saturating_sub(span.width);
+		}
+		(y + height <= self.height).then_some(y)
+	}
+	///Joins neighbouring spans at the same height
+	fn merge(&mut self) {
+		let mut index = 0;
+		while index + 1 < self.skyline.len() {
+			if self.skyline[index].y == self.skyline[index + 1].y {
+				self.skyline[index].width += self.skyline[index + 1].width;
+				self.skyline.remove(index + 1);
+			} else {
+				index += 1;
+			}
+		}
+	}
+}
+
+///How a [`TextureAtlas`] lays out its pages
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AtlasConfig {
+	///The width and height of each page in pixels. This must be no more than the device's `max_texture_dimension_2d`.
+	///
+	///Defaults to 1024
+	pub page_size: u32,
+	///The empty pixels left between entries.
+	///
+	///Defaults to 2
+	pub padding: u32,
+	///How many times the edge pixels of each entry are repeated around it, so filtering at the edge of an entry doesn't blend in its neighbours or the padding.
+	///
+	///Defaults to 1
+	pub extrusion: u32,
+	///How the colours of the pages are stored.
+	///
+	///Defaults to `Srgb`
+	pub colour_space: ColourSpace,
+	///How the pages are sampled. Repeating samplers don't tile entries, as they would tile the whole page.
+	///
+	///Defaults to `LinearClamp`
+	pub sampler: SamplerPreset
+}
+impl Default for AtlasConfig {
+	fn default() -> Self {
+		AtlasConfig {
+			page_size: 1024,
+			padding: 2,
+			extrusion: 1,
+			colour_space: ColourSpace::Srgb,
+			sampler: SamplerPreset::LinearClamp
+		}
+	}
+}
+
+///Where an entry was packed in a [`TextureAtlas`]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AtlasEntry {
+	///The index of the page the entry is on
+	pub page: usize,
+	///The pixels of the entry on its page, not including its extrusion
+	pub rect: AtlasRect,
+	///The texture coordinates of the entry on its page
+	pub uv: UvRect
+}
+///An area of a texture in texture coordinates, from 0 to 1 with the origin at the top left
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct UvRect {
+	///The top left corner
+	pub min: [f32; 2],
+	///The bottom right corner
+	pub max: [f32; 2]
+}
+
+///Describes why an image couldn't be added to a [`TextureAtlas`]
+#[derive(Debug)]
+pub enum AtlasError {
+	///The image, with its padding and extrusion, is bigger than a page. Sizes are in pixels
+	TooLarge { width: u32, height: u32, page_size: u32 },
+	///The image has no pixels, so there is nothing to pack
+	Empty { width: u32, height: u32 },
+	///An entry with the name already exists
+	NameTaken(String),
+	///The image couldn't be read or decoded
+	Image(image::ImageError)
+}
+impl Display for AtlasError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			AtlasError::TooLarge { width, height, page_size } => write!(f, "The image is {width}x{height} with its padding and extrusion, which doesn't fit in the atlas's {page_size}x{page_size} pages"),
+			AtlasError::Empty { width, height } => write!(f, "The image is {width}x{height}, which has no pixels to pack"),
+			AtlasError::NameTaken(name) => write!(f, "The atlas already has an entry named {name}"),
+			AtlasError::Image(error) => write!(f, "The image couldn't be loaded: {error}")
+		}
+	}
+}
+impl Error for AtlasError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			AtlasError::Image(error) => Some(error),
+			_ => None
+		}
+	}
+}
+
+///Packs many small images into a few large textures, so draws that use different images can share a bind group.
+///
+///Images are packed on the CPU as they're added, with a new page made when none of them have room. [`TextureAtlas::upload`] then makes a texture for each new page and writes the changes to the others,
+///so entries can be added at any time.\
+///Pages don't have mips, as the mips would blend entries together.
+///
+///# Usage
+///```rs
+/// let mut atlas = TextureAtlas::new(Some("Sprites"), AtlasConfig::default());
+/// let player = atlas.add_path("player", "sprites/player.png")?;
+/// let enemy = atlas.add_path("enemy", "sprites/enemy.png")?;
+/// atlas.upload(&mut my_heatwave_app)?;
+///
+/// let page = atlas.page_texture(player.page).unwrap();
+/// let bind_group = my_heatwave_app.add_bind_group(0, &[
+///     BindingResource::TextureView(page),
+///     BindingResource::TextureSampler(page)
+/// ])?;
+///```
+pub struct TextureAtlas {
+	label: Option<String>,
+	config: AtlasConfig,
+	pages: Vec<AtlasPage>,
+	entries: HashMap<String, AtlasEntry>
+}
+struct AtlasPage {
+	packer: AtlasPacker,
+	image: RgbaImage,
+	texture: Option<TextureHandle>,
+	///The area changed since the last upload
+	dirty: Option<AtlasRect>
+}
+impl TextureAtlas {
+	///Creates an empty atlas. The label is used for the textures of its pages
+	pub fn new(label: Option<&str>, config: AtlasConfig) -> Self {
+		TextureAtlas {
+			label: label.map(String::from),
+			config,
+			pages: Vec::new(),
+			entries: HashMap::new()
+		}
+	}
+
+	///Packs the image into the first page with room for it, making a new page if none have room.\
+	///The image is only on the CPU until the next [`TextureAtlas::upload`]
+	///
+	///# Errors
+	/// Errors if an entry with the name already exists, or if the image is empty or doesn't fit in a page
+	pub fn add(&mut self, name: &str, image: &DynamicImage) -> Result<AtlasEntry, AtlasError> {
+		if self.entries.contains_key(name) {
+			return Err(AtlasError::NameTaken(name.to_string()));
+		}
+		if image.width() == 0 || image.height() == 0 {
+			return Err(AtlasError::Empty { width: image.width(), height: image.height() });
+		}
+
+		let image = image.to_rgba8();
+		let extrusion = self.config.extrusion;
+		let padding = self.config.padding;
+		//Padding goes after each entry, so the packer gets room for it past the edge of the page
+		let width = image.width() + extrusion * 2 + padding;
+		let height = image.height() + extrusion * 2 + padding;
+		let packer_size = self.config.page_size + padding;
+		if width > packer_size || height > packer_size {
+			return Err(AtlasError::TooLarge { width: width - padding, height: height - padding, page_size: self.config.page_size });
+		}
+
+		let packed = self.pages.iter_mut()
+			.enumerate()
+			.find_map(|(page, atlas_page)| atlas_page.packer.pack(width, height).map(|rect| (page, rect)));
+		let (page, packed) = match packed {
+			Some(packed) => packed,
+			None => {
+				let mut packer = AtlasPacker::new(packer_size, packer_size);
+				let rect = packer.pack(width, height).expect("Expected an image that fits in a page to fit in an empty page");
+				self.pages.push(AtlasPage {
+					packer,
+					image: RgbaImage::new(self.config.page_size, self.config.page_size),
+					texture: None,
+					dirty: None
+				});
+				(self.pages.len() - 1, rect)
+			}
+		};
+
+		let atlas_page = &mut self.pages[page];
+		let extruded = AtlasRect {
+			x: packed.x,
+			y: packed.y,
+			width: width - padding,
+			height: height - padding
+		};
+		for y in 0..extruded.height {
+			for x in 0..extruded.width {
+				//Pixels outside the image take the colour of the nearest edge pixel
+				let source_x = x.saturating_sub(extrusion).min(image.width() - 1);
+				let source_y = y.saturating_sub(extrusion).min(image.height() - 1);
+				atlas_page.image.put_pixel(extruded.x + x, extruded.y + y, *image.get_pixel(source_x, source_y));
+			}
+		}
+		atlas_page.dirty = Some(match atlas_page.dirty {
+			Some(dirty) => {
+				let x = dirty.x.min(extruded.x);
+				let y = dirty.y.min(extruded.y);
+				AtlasRect {
+					x,
+					y,
+					width: (dirty.x + dirty.width).max(extruded.x + extruded.width) - x,
+					height: (dirty.y + dirty.height).max(extruded.y + extruded.height) - y
+				}
+			},
+			None => extruded
+		});
+
+		let rect = AtlasRect {
+			x: packed.x + extrusion,
+			y: packed.y + extrusion,
+			width: image.width(),
+			height: image.height()
+		};
+		let page_size = self.config.page_size as f32;
+		let entry = AtlasEntry {
+			page,
+			rect,
+			uv: UvRect {
+				min: [rect.x as f32 / page_size, rect.y as f32 / page_size],
+				max: [(rect.x + rect.width) as f32 / page_size, (rect.y + rect.height) as f32 / page_size]
+			}
+		};
+		self.entries.insert(name.to_string(), entry);
+		Ok(entry)
+	}
+	///Loads an image file, such as a PNG, and packs it. See [`TextureAtlas::add`]
+	///
+	///# Errors
+	/// Errors if the file can't be read or decoded, if an entry with the name already exists, or if the image is empty or doesn't fit in a page
+	pub fn add_path(&mut self, name: &str, path: impl AsRef<Path>) -> Result<AtlasEntry, AtlasError> {
+		let image = image::open(path).map_err(AtlasError::Image)?;
+		self.add(name, &image)
+	}
+	///Makes a texture in the app for each page that doesn't have one yet, and writes the entries added since the last upload to the others.\
+	///The writes happen before the next work submitted to the GPU
+	///
+	///Pages whose texture was removed from the app, such as by a lost device, are made again.
+	///
+	///# Errors
+	/// Errors if the page size is larger than the device's `max_texture_dimension_2d`
+	pub fn upload(&mut self, app: &mut HeatwaveApp) -> Result<(), TextureError> {
+		let options = TextureOptions {
+			label: self.label.as_deref(),
+			colour_space: self.config.colour_space,
+			sampler: self.config.sampler,
+			generate_mipmaps: false
+		};
+
+		for page in &mut self.pages {
+			let Some(texture) = page.texture.and_then(|handle| app.texture(handle)) else {
+				let texture = Texture::from_image(app.connection().device(), app.connection().queue(), &DynamicImage::ImageRgba8(page.image.clone()), &options)?;
+				page.texture = Some(app.add_texture(texture, options.label));
+				page.dirty = None;
+				continue;
+			};
+			let Some(dirty) = page.dirty.take() else {
+				continue;
+			};
+
+			let pixels = image::imageops::crop_imm(&page.image, dirty.x, dirty.y, dirty.width, dirty.height).to_image();
+			app.connection().queue().write_texture(
+				wgpu::ImageCopyTexture {
+					texture: &texture.texture,
+					mip_level: 0,
+					origin: wgpu::Origin3d { x: dirty.x, y: dirty.y, z: 0 },
+					aspect: wgpu::TextureAspect::All
+				},
+				&pixels,
+				wgpu::ImageDataLayout {
+					offset: 0,
+					bytes_per_row: Some(4 * dirty.width),
+					rows_per_image: Some(dirty.height)
+				},
+				wgpu::Extent3d {
+					width: dirty.width,
+					height: dirty.height,
+					depth_or_array_layers: 1
+				}
+			);
+		}
+		Ok(())
+	}
+	///Removes the textures of every page from the app
+	pub fn remove(self, app: &mut HeatwaveApp) {
+		for texture in self.pages.into_iter().filter_map(|page| page.texture) {
+			app.remove_texture(texture);
+		}
+	}
+
+	///Where the entry with the name was packed
+	pub fn get(&self, name: &str) -> Option<AtlasEntry> {
+		self.entries.get(name).copied()
+	}
+	///Every entry and its name, in no particular order
+	pub fn entries(&self) -> impl Iterator<Item = (&str, AtlasEntry)> {
+		self.entries.iter().map(|(name, entry)| (name.as_str(), *entry))
+	}
+	///The number of entries
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+	///The number of pages made
+	pub fn page_count(&self) -> usize {
+		self.pages.len()
+	}
+	///The CPU copy of the page, which includes entries that haven't been uploaded
+	pub fn page_image(&self, page: usize) -> Option<&RgbaImage> {
+		self.pages.get(page).map(|page| &page.image)
+	}
+	///The texture of the page, or `None` if the page hasn't been uploaded yet
+	pub fn page_texture(&self, page: usize) -> Option<TextureHandle> {
+		self.pages.get(page).and_then(|page| page.texture)
+	}
+}
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+///Draws a triangle covering the target, sampling the level above it
+const BLIT_SHADER: &str = "
+struct VertexOutput {
+	@builtin(position) position: vec4<f32>,
+	@location(0) uv: vec2<f32>
+}
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var source_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> VertexOutput {
+	let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+	var output: VertexOutput;
+	output.position = vec4<f32>(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0, 0.0, 1.0);
+	output.uv = uv;
+	return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+	return textureSample(source, source_sampler, input.uv);
+}
+";
+
+///The number of levels in a full mip chain for a texture of this size, down to 1x1
+pub(crate) fn mip_level_count(width: u32, height: u32) -> u32 {
+	u32::BITS - width.max(height).leading_zeros()
+}
+
+///Makes the mips of textures on the GPU by rendering a linearly filtered copy of each level into the one below it.\
+///The blit pipeline for each format is made the first time a texture of that format needs mips, and reused after
+#[derive(Default)]
+pub(crate) struct MipmapGenerator {
+	shader: Option<wgpu::ShaderModule>,
+	sampler: Option<wgpu::Sampler>,
+	pipelines: HashMap<wgpu::TextureFormat, wgpu::RenderPipeline>
+}
+impl MipmapGenerator {
+	///Fills every level after the first from the level above it.\
+	///The first level must already be written, and the texture must be usable as a render attachment and a texture binding
+	pub(crate) fn generate(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, texture: &wgpu::Texture) {
+		let shader = self.shader.get_or_insert_with(|| device.create_shader_module(wgpu::ShaderModuleDescriptor {
+			label: Some("Heatwave Mipmap Shader"),
+			source: wgpu::ShaderSource::Wgsl(BLIT_SHADER.into())
+		}));
+		let pipeline = self.pipelines.entry(texture.format()).or_insert_with(|| device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+			label: Some("Heatwave Mipmap Pipeline"),
+			layout: None,
+			vertex: wgpu::VertexState {
+				module: shader,
+				entry_point: "vs_main",
+				buffers: &[]
+			},
+			fragment: Some(wgpu::FragmentState {
+				module: shader,
+				entry_point: "fs_main",
+				targets: &[Some(texture.format().into())]
+			}),
+			primitive: wgpu::PrimitiveState::default(),
+			depth_stencil: None,
+			multisample: wgpu::MultisampleState::default(),
+			multiview: None
+		}));
+		let sampler = self.sampler.get_or_insert_with(|| device.create_sampler(&wgpu::SamplerDescriptor {
+			label: Some("Heatwave Mipmap Sampler"),
+			mag_filter: wgpu::FilterMode::Linear,
+			min_filter: wgpu::FilterMode::Linear,
+			..Default::default()
+		}));
+		let layout = pipeline.get_bind_group_layout(0);
+
+		let views: Vec<wgpu::TextureView> = (0..texture.mip_level_count()).map(|level| texture.create_view(&wgpu::TextureViewDescriptor {
+			label: Some("Heatwave Mipmap View"),
+			base_mip_level: level,
+			mip_level_count: Some(1),
+			..Default::default()
+		})).collect();
+
+		let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("Heatwave Mipmap Encoder") });
+		for levels in views.windows(2) {
+			let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+				label: Some("Heatwave Mipmap Bind Group"),
+				layout: &layout,
+				entries: &[
+					wgpu::BindGroupEntry {
+						binding: 0,
+						resource: wgpu::BindingResource::TextureView(&levels[0])
+					},
+					wgpu::BindGroupEntry {
+						binding: 1,
+						resource: wgpu::BindingResource::Sampler(sampler)
+					}
+				]
+			});
+
+			let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+				label: Some("Heatwave Mipmap Pass"),
+				color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+					view: &levels[1],
+					resolve_target: None,
+					ops: wgpu::Operations {
+						load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+						store: wgpu::StoreOp::Store
+					}
+				})],
+				depth_stencil_attachment: None,
+				timestamp_writes: None,
+				occlusion_query_set: None
+			});
+			pass.set_pipeline(pipeline);
+			pass.set_bind_group(0, &bind_group, &[]);
+			pass.draw(0..3, 0..1);
+		}
+		queue.submit(Some(encoder.finish()));
+	}
+}
@@ -0,0 +1,358 @@
+use std::{
+	collections::{HashMap, HashSet},
+	error::Error,
+	fmt::Display,
+	io,
+	path::{Component, Path, PathBuf}
+};
+
+///Expands `#include`, `#define` and conditional directives in WGSL, keeping track of where each line came from.
+///
+///Directives are lines starting with `#`, which can be indented:
+/// - `#include "path"` inserts another file, relative to the file including it. Each file is only inserted the first time it's included
+/// - `#define NAME value` replaces every later use of `NAME` with `value`. The value can be left out to only mark `NAME` as defined
+/// - `#ifdef NAME`, `#ifndef NAME`, `#else` and `#endif` keep or remove lines based on whether `NAME` is a feature or has been defined
+///
+///```rs
+///let shader = ShaderPreprocessor::new()
+///    .feature("SHADOWS")
+///    .define("MAX_LIGHTS", "8")
+///    .process_file("shaders/lit.wgsl")?;
+///let handle = app.add_shader(shader.descriptor(Some("Lit")))?;
+///```
+#[derive(Clone, Default, Debug)]
+pub struct ShaderPreprocessor {
+	features: HashSet<String>,
+	defines: HashMap<String, String>
+}
+impl ShaderPreprocessor {
+	///Creates a preprocessor with no features or constants
+	pub fn new() -> Self {
+		Self::default()
+	}
+	///Enables a feature, so `#ifdef` blocks checking for it are kept
+	pub fn feature(mut self, name: &str) -> Self {
+		self.features.insert(name.to_string());
+		self
+	}
+	///Defines a constant before the shader is read, as if it started with `#define name value`
+	pub fn define(mut self, name: &str, value: &str) -> Self {
+		self.defines.insert(name.to_string(), value.to_string());
+		self
+	}
+	///Reads and expands the file at `path`, along with every file it includes
+	///
+	///# Errors
+	/// Errors if a file can't be read, the includes form a cycle, or a directive is malformed
+	pub fn process_file(&self, path: impl AsRef<Path>) -> Result<PreprocessedShader, PreprocessError> {
+		self.process_with(path, |path| std::fs::read_to_string(path))
+	}
+	///Expands the file at `path`, using `load` to read it and every file it includes. Useful for shaders that aren't on disk, such as ones embedded in the binary
+	///
+	///# Errors
+	/// Errors if `load` fails, the includes form a cycle, or a directive is malformed
+	pub fn process_with(&self, path: impl AsRef<Path>, mut load: impl FnMut(&Path) -> io::Result<String>) -> Result<PreprocessedShader, PreprocessError> {
+		let path = normalise(path.as_ref());
+		let source = load(&path).map_err(|source| PreprocessError::Read { path: path.clone(), included_from: None, source })?;
+
+		let mut expansion = Expansion {
+			preprocessor: self,
+			defines: self.defines.clone(),
+			shader: PreprocessedShader::default(),
+			including: Vec::new()
+		};
+		expansion.expand(&path, &source, &mut load)?;
+		Ok(expansion.shader)
+	}
+}
+
+///WGSL produced by a [`ShaderPreprocessor`], along with where each of its lines came from
+#[derive(Clone, Default, Debug)]
+pub struct PreprocessedShader {
+	source: String,
+	///The file index and line number of each line in the source
+	lines: Vec<(usize, usize)>,
+	files: Vec<PathBuf>
+}
+impl PreprocessedShader {
+	///The expanded WGSL
+	pub fn source(&self) -> &str {
+		&self.source
+	}
+	///Every file that was read, starting with the file that was processed. Useful for knowing which files to watch for changes
+	pub fn files(&self) -> &[PathBuf] {
+		&self.files
+	}
+	///Returns where a line of the expanded source came from. Lines are counted from 1, as they are in naga's errors
+	pub fn location(&self, line: usize) -> Option<SourceLocation> {
+		let (file, line) = *self.lines.get(line.checked_sub(1)?)?;
+		Some(SourceLocation {
+			path: self.files[file].clone(),
+			line
+		})
+	}
+	///Rewrites the `wgsl:line:column` locations in an error from naga or wgpu so they point at the original file and line.\
+	///Columns are left as they are, so they can be off on lines where a constant was replaced
+	pub fn map_error(&self, message: &str) -> String {
+		const PREFIX: &str = "wgsl:";
+		let mut mapped = String::with_capacity(message.len());
+		let mut rest = message;
+		while let Some(start) = rest.find(PREFIX) {
+			mapped.push_str(&rest[..start]);
+			let after = &rest[start + PREFIX.len()..];
+			let digits = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+			match after[..digits].parse().ok().and_then(|line| self.location(line)) {
+				Some(location) => {
+					mapped.push_str(&location.to_string());
+					rest = &after[digits..];
+				}
+				None => {
+					mapped.push_str(PREFIX);
+					rest = after;
+				}
+			}
+		}
+		mapped.push_str(rest);
+		mapped
+	}
+	///Makes a descriptor for the expanded WGSL, for use with [`HeatwaveApp::add_shader`](crate::HeatwaveApp::add_shader)
+	pub fn descriptor<'a>(&'a self, label: Option<&'a str>) -> wgpu::ShaderModuleDescriptor<'a> {
+		wgpu::ShaderModuleDescriptor {
+			label,
+			source: wgpu::ShaderSource::Wgsl(self.source.as_str().into())
+		}
+	}
+}
+
+///A line in one of the files read by a [`ShaderPreprocessor`]
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SourceLocation {
+	///The file the line is in
+	pub path: PathBuf,
+	///The line number, counted from 1
+	pub line: usize
+}
+impl Display for SourceLocation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}:{}", self.path.display(), self.line)
+	}
+}
+
+///Describes why a shader couldn't be preprocessed
+#[derive(Debug)]
+pub enum PreprocessError {
+	///A file couldn't be read
+	Read {
+		///The file that couldn't be read
+		path: PathBuf,
+		///The `#include` that asked for the file, or `None` if it was the file being processed
+		included_from: Option<SourceLocation>,
+		source: io::Error
+	},
+	///A file includes itself, directly or through other files
+	IncludeCycle {
+		///The files in the cycle, starting and ending with the file included again
+		chain: Vec<PathBuf>,
+		///The `#include` that closed the cycle
+		location: SourceLocation
+	},
+	///A directive couldn't be used
+	Directive {
+		///Where the directive is
+		location: SourceLocation,
+		kind: DirectiveError
+	}
+}
+///Why a directive couldn't be used
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DirectiveError {
+	///The directive isn't one the preprocessor knows
+	Unknown(String),
+	///The directive's argument is missing or isn't valid, such as an `#include` path without quotes
+	Malformed(String),
+	///An `#else` or `#endif` has no `#ifdef` or `#ifndef` to go with it
+	Unmatched(String),
+	///An `#ifdef` or `#ifndef` has no `#endif` before the end of its file
+	Unterminated
+}
+impl Display for PreprocessError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			PreprocessError::Read { path, included_from: Some(location), source } => write!(f, "{location}: Failed to read included file \"{}\": {source}", path.display()),
+			PreprocessError::Read { path, included_from: None, source } => write!(f, "Failed to read shader \"{}\": {source}", path.display()),
+			PreprocessError::IncludeCycle { chain, location } => {
+				let chain: Vec<String> = chain.iter().map(|path| path.display().to_string()).collect();
+				write!(f, "{location}: Include cycle found ({})", chain.join(" -> "))
+			}
+			PreprocessError::Directive { location, kind } => match kind {
+				DirectiveError::Unknown(name) => write!(f, "{location}: Unknown directive #{name}"),
+				DirectiveError::Malformed(name) => write!(f, "{location}: Malformed #{name} directive"),
+				DirectiveError::Unmatched(name) => write!(f, "{location}: #{name} without a matching #ifdef or #ifndef"),
+				DirectiveError::Unterminated => write!(f, "{location}: Conditional block isn't closed with #endif")
+			}
+		}
+	}
+}
+impl Error for PreprocessError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			PreprocessError::Read { source, .. } => Some(source),
+			_ => None
+		}
+	}
+}
+
+///An `#ifdef` or `#ifndef` block that hasn't been closed yet
+struct Conditional {
+	///Whether lines are currently being kept
+	active: bool,
+	///Whether lines were being kept outside of the block
+	parent_active: bool,
+	in_else: bool,
+	line: usize
+}
+
+///The state of a single [`ShaderPreprocessor::process_with`] call
+struct Expansion<'a> {
+	preprocessor: &'a ShaderPreprocessor,
+	defines: HashMap<String, String>,
+	shader: PreprocessedShader,
+	///The files currently being expanded, outermost first
+	including: Vec<PathBuf>
+}
+impl Expansion<'_> {
+	fn expand(&mut self, path: &Path, source: &str, load: &mut impl FnMut(&Path) -> io::Result<String>) -> Result<(), PreprocessError> {
+		let file = self.shader.files.len();
+		self.shader.files.push(path.to_path_buf());
+		self.including.push(path.to_path_buf());
+
+		let mut conditionals: Vec<Conditional> = Vec::new();
+		for (index, line) in source.lines().enumerate() {
+			let line_number = index + 1;
+			let location = || SourceLocation { path: path.to_path_buf(), line: line_number };
+			let directive_error = |kind| PreprocessError::Directive { location: location(), kind };
+			let active = conditionals.iter().all(|conditional| conditional.active);
+
+			let Some(directive) = line.trim_start().strip_prefix('#') else {
+				if active {
+					self.shader.source.push_str(&substitute(line, &self.defines));
+					self.shader.source.push('\n');
+					self.shader.lines.push((file, line_number));
+				}
+				continue;
+			};
+			let directive = strip_comment(directive).trim();
+			let (name, argument) = directive.split_once(char::is_whitespace).map_or((directive, ""), |(name, argument)| (name, argument.trim()));
+
+			match name {
+				"ifdef" | "ifndef" => {
+					let identifier = as_identifier(argument).ok_or_else(|| directive_error(DirectiveError::Malformed(name.to_string())))?;
+					let defined = self.preprocessor.features.contains(identifier) || self.defines.contains_key(identifier);
+					conditionals.push(Conditional {
+						active: active && defined == (name == "ifdef"),
+						parent_active: active,
+						in_else: false,
+						line: line_number
+					});
+				}
+				"else" => {
+					let conditional = conditionals.last_mut().filter(|conditional| !conditional.in_else).ok_or_else(|| directive_error(DirectiveError::Unmatched(name.to_string())))?;
+					conditional.active = conditional.parent_active && !conditional.active;
+					conditional.in_else = true;
+				}
+				"endif" => {
+					conditionals.pop().ok_or_else(|| directive_error(DirectiveError::Unmatched(name.to_string())))?;
+				}
+				_ if !active => {}
+				"define" => {
+					let (identifier, value) = argument.split_once(char::is_whitespace).unwrap_or((argument, ""));
+					let identifier = as_identifier(identifier).ok_or_else(|| directive_error(DirectiveError::Malformed(name.to_string())))?;
+					let value = substitute(value.trim(), &self.defines);
+					self.defines.insert(identifier.to_string(), value);
+				}
+				"include" => {
+					let included = argument
+						.strip_prefix('"')
+						.and_then(|argument| argument.strip_suffix('"'))
+						.filter(|included| !included.is_empty())
+						.ok_or_else(|| directive_error(DirectiveError::Malformed(name.to_string())))?;
+					let included = normalise(&path.parent().unwrap_or(Path::new("")).join(included));
+
+					if let Some(start) = self.including.iter().position(|including| *including == included) {
+						let mut chain = self.including[start..].to_vec();
+						chain.push(included);
+						return Err(PreprocessError::IncludeCycle { chain, location: location() });
+					}
+					if self.shader.files.contains(&included) {
+						continue;
+					}
+					let source = load(&included).map_err(|source| PreprocessError::Read { path: included.clone(), included_from: Some(location()), source })?;
+					self.expand(&included, &source, load)?;
+				}
+				_ => return Err(directive_error(DirectiveError::Unknown(name.to_string())))
+			}
+		}
+
+		if let Some(conditional) = conditionals.last() {
+			return Err(PreprocessError::Directive {
+				location: SourceLocation { path: path.to_path_buf(), line: conditional.line },
+				kind: DirectiveError::Unterminated
+			});
+		}
+		self.including.pop();
+		Ok(())
+	}
+}
+
+///Removes a trailing `//` comment from the directive. A `//` inside quotes, such as in an include path, isn't a comment
+fn strip_comment(directive: &str) -> &str {
+	let mut quoted = false;
+	let mut previous = None;
+	for (index, c) in directive.char_indices() {
+		match c {
+			'"' => quoted = !quoted,
+			'/' if !quoted && previous == Some('/') => return &directive[..index - 1],
+			_ => {}
+		}
+		previous = Some(c);
+	}
+	directive
+}
+
+///Returns the argument if it's a valid name for a define or feature
+fn as_identifier(argument: &str) -> Option<&str> {
+	let mut chars = argument.chars();
+	let first = chars.next()?;
+	((first.is_ascii_alphabetic() || first == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')).then_some(argument)
+}
+
+///Replaces every whole word in the line that has been defined with its value. Numbers, such as `1e5`, are never replaced
+fn substitute(line: &str, defines: &HashMap<String, String>) -> String {
+	let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+	let mut substituted = String::with_capacity(line.len());
+	let mut rest = line;
+	while let Some(start) = rest.find(is_word) {
+		let end = rest[start..].find(|c: char| !is_word(c)).map_or(rest.len(), |end| start + end);
+		let word = &rest[start..end];
+		substituted.push_str(&rest[..start]);
+		substituted.push_str(defines.get(word).map_or(word, String::as_str));
+		rest = &rest[end..];
+	}
+	substituted.push_str(rest);
+	substituted
+}
+
+///Removes `.` and `..` from the path without touching the file system, so a file included through different paths is recognised as the same file
+fn normalise(path: &Path) -> PathBuf {
+	let mut normalised = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {}
+			Component::ParentDir if matches!(normalised.components().next_back(), Some(Component::Normal(_))) => {
+				normalised.pop();
+			}
+			component => normalised.push(component)
+		}
+	}
+	normalised
+}
@@ -0,0 +1,486 @@
+use std::{
+	error::Error,
+	fmt::Display,
+	num::{NonZeroU32, NonZeroU64}
+};
+
+use wgpu::naga;
+
+use super::ShaderObject;
+
+///What a WGSL shader expects from the pipelines using it, found by reading the shader with naga.
+///
+///Use it to make bind group layouts and pipeline layouts that match the shader, and to check vertex buffer layouts against the shader's inputs:
+///```rs
+///let reflection = ShaderReflection::from_wgsl(source)?;
+///reflection.check_shader_object::<Vertex>("vs_main")?;
+///
+///let layout = reflection.create_layout(app.connection().device(), Some("Lit Layout"));
+///```
+#[derive(Clone, Debug)]
+pub struct ShaderReflection {
+	entry_points: Vec<EntryPoint>,
+	bindings: Vec<ReflectedBinding>,
+	push_constants: Vec<wgpu::PushConstantRange>
+}
+impl ShaderReflection {
+	///Parses and validates the WGSL, then reads what it expects from the pipelines using it
+	///
+	///# Errors
+	/// Errors if the WGSL doesn't parse, naga rejects the shader, or a binding array has no size. The messages use `wgsl:line:column` locations, so [`PreprocessedShader::map_error`](super::PreprocessedShader::map_error) can point them at the original files
+	pub fn from_wgsl(source: &str) -> Result<Self, ReflectionError> {
+		let module = naga::front::wgsl::parse_str(source).map_err(|error| ReflectionError::Parse(error.emit_to_string(source)))?;
+		let info = naga::valid::Validator::new(naga::valid::ValidationFlags::all(), naga::valid::Capabilities::all())
+			.validate(&module)
+			.map_err(|error| ReflectionError::Validation(error.emit_to_string(source)))?;
+
+		//The stages that use a global, going by the entry points that touch it
+		let visibility = |global: naga::Handle<naga::GlobalVariable>| {
+			module.entry_points.iter().enumerate()
+				.filter(|(index, _)| !info.get_entry_point(*index)[global].is_empty())
+				.fold(wgpu::ShaderStages::NONE, |stages, (_, entry_point)| stages | stage(entry_point.stage))
+		};
+
+		let entry_points = module.entry_points.iter().map(|entry_point| EntryPoint {
+			name: entry_point.name.clone(),
+			stage: stage(entry_point.stage),
+			vertex_inputs: if entry_point.stage == naga::ShaderStage::Vertex { vertex_inputs(&module, &entry_point.function) } else { Vec::new() },
+			workgroup_size: entry_point.workgroup_size
+		}).collect();
+
+		let mut bindings = Vec::new();
+		let mut push_constants = Vec::new();
+		for (handle, global) in module.global_variables.iter() {
+			if global.space == naga::AddressSpace::PushConstant {
+				let stages = visibility(handle);
+				if !stages.is_empty() {
+					push_constants.push(wgpu::PushConstantRange { stages, range: 0..module.types[global.ty].inner.size(module.to_ctx()) });
+				}
+				continue;
+			}
+			let Some(binding) = &global.binding else {
+				continue;
+			};
+			if matches!(module.types[global.ty].inner, naga::TypeInner::BindingArray { size: naga::ArraySize::Dynamic, .. }) {
+				return Err(ReflectionError::UnboundedBindingArray { group: binding.group, binding: binding.binding, name: global.name.clone() });
+			}
+			if let Some((ty, size, count)) = binding_type(&module, global.space, global.ty) {
+				bindings.push(ReflectedBinding {
+					group: binding.group,
+					binding: binding.binding,
+					name: global.name.clone(),
+					ty,
+					visibility: visibility(handle),
+					size,
+					count
+				});
+			}
+		}
+		bindings.sort_by_key(|binding| (binding.group, binding.binding));
+
+		Ok(ShaderReflection { entry_points, bindings, push_constants })
+	}
+	///Every entry point in the shader
+	pub fn entry_points(&self) -> &[EntryPoint] {
+		&self.entry_points
+	}
+	///Returns the entry point with the name given, if it exists
+	pub fn entry_point(&self, name: &str) -> Option<&EntryPoint> {
+		self.entry_points.iter().find(|entry_point| entry_point.name == name)
+	}
+	///Every resource the shader binds, sorted by group and then binding
+	pub fn bindings(&self) -> &[ReflectedBinding] {
+		&self.bindings
+	}
+	///The push constant ranges used by the shader, with the stages that use each one
+	pub fn push_constants(&self) -> &[wgpu::PushConstantRange] {
+		&self.push_constants
+	}
+	///The number of bind groups a pipeline layout needs for the shader, which is one more than the highest group used
+	pub fn bind_group_count(&self) -> u32 {
+		self.bindings.last().map_or(0, |binding| binding.group + 1)
+	}
+	///The layout entries for a group, which can be used for a [`wgpu::BindGroupLayoutDescriptor`] in [`HeatwaveConfig::bind_groups`](crate::HeatwaveConfig::bind_groups).\
+	///Groups the shader doesn't use have no entries
+	pub fn bind_group_layout_entries(&self, group: u32) -> Vec<wgpu::BindGroupLayoutEntry> {
+		self.bindings.iter().filter(|binding| binding.group == group).map(ReflectedBinding::layout_entry).collect()
+	}
+	///Creates a bind group layout for each group the shader uses, along with a pipeline layout made from them and the shader's push constants.\
+	///Groups the shader skips are given empty layouts. Push constants need `Features::PUSH_CONSTANTS`
+	pub fn create_layout(&self, device: &wgpu::Device, label: Option<&str>) -> ReflectedLayout {
+		let bind_group_layouts: Vec<wgpu::BindGroupLayout> = (0..self.bind_group_count()).map(|group| device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+			label,
+			entries: &self.bind_group_layout_entries(group)
+		})).collect();
+		let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+			label,
+			bind_group_layouts: &bind_group_layouts.iter().collect::<Vec<_>>(),
+			push_constant_ranges: &self.push_constants
+		});
+		ReflectedLayout { bind_group_layouts, pipeline_layout }
+	}
+	///Checks that the vertex buffer layouts provide every input of the vertex entry point, each with a format the shader reads as the same type.\
+	///Attributes the shader doesn't read are allowed, so one layout can be shared between shaders
+	///
+	///# Errors
+	/// Errors if there is no vertex entry point with the name given, an input isn't provided, or an attribute's format doesn't match its input's type
+	pub fn check_vertex_layout(&self, entry_point: &str, buffers: &[wgpu::VertexBufferLayout]) -> Result<(), VertexLayoutError> {
+		let entry_point = self.entry_point(entry_point)
+			.filter(|found| found.stage == wgpu::ShaderStages::VERTEX)
+			.ok_or_else(|| VertexLayoutError::UnknownEntryPoint(entry_point.to_string()))?;
+
+		for input in &entry_point.vertex_inputs {
+			let attribute = buffers.iter()
+				.flat_map(|buffer| buffer.attributes)
+				.find(|attribute| attribute.shader_location == input.location)
+				.ok_or_else(|| VertexLayoutError::MissingAttribute { location: input.location, name: input.name.clone() })?;
+			if VertexInputType::of_format(attribute.format) != input.ty {
+				return Err(VertexLayoutError::FormatMismatch { location: input.location, format: attribute.format, expected: input.ty });
+			}
+		}
+		Ok(())
+	}
+	///Checks the [`ShaderObject`]'s layout against the inputs of the vertex entry point. See [`ShaderReflection::check_vertex_layout`]
+	///
+	///# Errors
+	/// Errors if there is no vertex entry point with the name given, an input isn't provided, or an attribute's format doesn't match its input's type
+	pub fn check_shader_object<T: ShaderObject>(&self, entry_point: &str) -> Result<(), VertexLayoutError> {
+		self.check_vertex_layout(entry_point, &[T::layout()])
+	}
+}
+
+///A function in a shader that a pipeline can start from
+#[derive(Clone, PartialEq, Debug)]
+pub struct EntryPoint {
+	pub name: String,
+	///The stage the entry point runs in
+	pub stage: wgpu::ShaderStages,
+	///The locations read from vertex buffers, sorted by location. Empty for anything but vertex entry points
+	pub vertex_inputs: Vec<VertexInput>,
+	///The size of each workgroup. Zero for anything but compute entry points
+	pub workgroup_size: [u32; 3]
+}
+
+///A value a vertex shader reads from a vertex buffer
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct VertexInput {
+	///The `@location` the value is read from
+	pub location: u32,
+	///The name of the argument or struct member, if it has one
+	pub name: Option<String>,
+	///The type the shader reads the value as
+	pub ty: VertexInputType
+}
+///The type a vertex shader reads an input as, such as `vec3<f32>`
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct VertexInputType {
+	pub scalar: VertexScalar,
+	///1 for a scalar, or the size of the vector
+	pub components: u32
+}
+///The scalar type of a vertex input
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VertexScalar {
+	F32,
+	F64,
+	I32,
+	U32
+}
+impl VertexInputType {
+	///The type the shader reads an attribute with this format as. Normalised formats are read as floats
+	pub fn of_format(format: wgpu::VertexFormat) -> Self {
+		use wgpu::VertexFormat as Vf;
+
+		let scalar = match format {
+			Vf::Uint8x2 | Vf::Uint8x4 | Vf::Uint16x2 | Vf::Uint16x4 | Vf::Uint32 | Vf::Uint32x2 | Vf::Uint32x3 | Vf::Uint32x4 => VertexScalar::U32,
+			Vf::Sint8x2 | Vf::Sint8x4 | Vf::Sint16x2 | Vf::Sint16x4 | Vf::Sint32 | Vf::Sint32x2 | Vf::Sint32x3 | Vf::Sint32x4 => VertexScalar::I32,
+			Vf::Float64 | Vf::Float64x2 | Vf::Float64x3 | Vf::Float64x4 => VertexScalar::F64,
+			_ => VertexScalar::F32
+		};
+		let components = match format {
+			Vf::Uint32 | Vf::Sint32 | Vf::Float32 | Vf::Float64 => 1,
+			Vf::Uint32x3 | Vf::Sint32x3 | Vf::Float32x3 | Vf::Float64x3 => 3,
+			Vf::Uint8x4 | Vf::Uint16x4 | Vf::Uint32x4 | Vf::Sint8x4 | Vf::Sint16x4 | Vf::Sint32x4 | Vf::Unorm8x4 | Vf::Snorm8x4 | Vf::Unorm16x4 | Vf::Snorm16x4 | Vf::Float16x4
+				| Vf::Float32x4 | Vf::Float64x4 => 4,
+			_ => 2
+		};
+		VertexInputType { scalar, components }
+	}
+}
+impl Display for VertexInputType {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let scalar = match self.scalar {
+			VertexScalar::F32 => "f32",
+			VertexScalar::F64 => "f64",
+			VertexScalar::I32 => "i32",
+			VertexScalar::U32 => "u32"
+		};
+		match self.components {
+			1 => write!(f, "{scalar}"),
+			components => write!(f, "vec{components}<{scalar}>")
+		}
+	}
+}
+
+///A resource bound by a shader
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ReflectedBinding {
+	///The `@group` the resource is bound in
+	pub group: u32,
+	///The `@binding` the resource is bound at
+	pub binding: u32,
+	///The name of the variable in the shader, if it has one
+	pub name: Option<String>,
+	///What kind of resource is bound. Float textures are assumed to be filterable and samplers to be filtering, as the shader can't say otherwise
+	pub ty: wgpu::BindingType,
+	///The stages of the shader's entry points that use the resource. Empty if no entry point uses it
+	pub visibility: wgpu::ShaderStages,
+	///The size of a buffer's type in bytes, or `None` for anything but buffers. For types ending in a runtime sized array, this includes one element of the array
+	pub size: Option<u64>,
+	///The number of resources in a binding array, or `None` if the binding isn't a fixed size array
+	pub count: Option<NonZeroU32>
+}
+impl ReflectedBinding {
+	///The layout entry for the binding
+	pub fn layout_entry(&self) -> wgpu::BindGroupLayoutEntry {
+		wgpu::BindGroupLayoutEntry {
+			binding: self.binding,
+			visibility: self.visibility,
+			ty: self.ty,
+			count: self.count
+		}
+	}
+}
+
+///The layouts made by [`ShaderReflection::create_layout`]
+#[derive(Debug)]
+pub struct ReflectedLayout {
+	///The layout of each group, indexed by group. Use these to make bind groups for the pipelines using the layout
+	pub bind_group_layouts: Vec<wgpu::BindGroupLayout>,
+	pub pipeline_layout: wgpu::PipelineLayout
+}
+
+///Describes why a shader couldn't be reflected
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ReflectionError {
+	///The WGSL couldn't be parsed. Contains naga's description of the error
+	Parse(String),
+	///naga rejected the shader. Contains naga's description of the error
+	Validation(String),
+	///A binding array has no size, so there's no count to put in its layout entry
+	UnboundedBindingArray {
+		group: u32,
+		binding: u32,
+		///The name of the binding in the shader, if it has one
+		name: Option<String>
+	}
+}
+impl Display for ReflectionError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			ReflectionError::Parse(message) => write!(f, "Failed to parse shader for reflection: {message}"),
+			ReflectionError::Validation(message) => write!(f, "Shader failed validation during reflection: {message}"),
+			ReflectionError::UnboundedBindingArray { group, binding, name: Some(name) } => write!(f, "The binding array \"{name}\" at group {group}, binding {binding} has no size, so its layout needs a count"),
+			ReflectionError::UnboundedBindingArray { group, binding, name: None } => write!(f, "The binding array at group {group}, binding {binding} has no size, so its layout needs a count")
+		}
+	}
+}
+impl Error for ReflectionError {}
+
+///Describes why a vertex buffer layout doesn't match a vertex shader's inputs
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum VertexLayoutError {
+	///The shader has no vertex entry point with this name
+	UnknownEntryPoint(String),
+	///No attribute provides an input the shader reads
+	MissingAttribute {
+		location: u32,
+		///The name of the input in the shader, if it has one
+		name: Option<String>
+	},
+	///An attribute's format is read as a different type than the shader expects
+	FormatMismatch {
+		location: u32,
+		format: wgpu::VertexFormat,
+		///The type the shader reads the input as
+		expected: VertexInputType
+	}
+}
+impl Display for VertexLayoutError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			VertexLayoutError::UnknownEntryPoint(name) => write!(f, "The shader has no vertex entry point named \"{name}\""),
+			VertexLayoutError::MissingAttribute { location, name: Some(name) } => write!(f, "No vertex attribute provides \"{name}\" at location {location}"),
+			VertexLayoutError::MissingAttribute { location, name: None } => write!(f, "No vertex attribute provides location {location}"),
+			VertexLayoutError::FormatMismatch { location, format, expected } => {
+				write!(f, "The vertex attribute at location {location} is {format:?}, which is read as {}, but the shader expects {expected}", VertexInputType::of_format(*format))
+			}
+		}
+	}
+}
+impl Error for VertexLayoutError {}
+
+fn stage(stage: naga::ShaderStage) -> wgpu::ShaderStages {
+	match stage {
+		naga::ShaderStage::Vertex => wgpu::ShaderStages::VERTEX,
+		naga::ShaderStage::Fragment => wgpu::ShaderStages::FRAGMENT,
+		naga::ShaderStage::Compute => wgpu::ShaderStages::COMPUTE
+	}
+}
+
+///Collects the location bound arguments of a vertex entry point, including the members of struct arguments
+fn vertex_inputs(module: &naga::Module, function: &naga::Function) -> Vec<VertexInput> {
+	let mut inputs = Vec::new();
+	let mut add = |name: &Option<String>, ty, binding: &Option<naga::Binding>| {
+		let location = match binding {
+			Some(naga::Binding::Location { location, .. }) => *location,
+			_ => return
+		};
+		let (scalar, components) = match &module.types[ty].inner {
+			naga::TypeInner::Scalar(scalar) => (scalar, 1),
+			naga::TypeInner::Vector { size, scalar } => (scalar, *size as u32),
+			_ => return
+		};
+		let scalar = match (scalar.kind, scalar.width) {
+			(naga::ScalarKind::Float, 8) => VertexScalar::F64,
+			(naga::ScalarKind::Float, _) => VertexScalar::F32,
+			(naga::ScalarKind::Sint, _) => VertexScalar::I32,
+			(naga::ScalarKind::Uint, _) => VertexScalar::U32,
+			_ => return
+		};
+		inputs.push(VertexInput { location, name: name.clone(), ty: VertexInputType { scalar, components } });
+	};
+
+	for argument in &function.arguments {
+		match &module.types[argument.ty].inner {
+			naga::TypeInner::Struct { members, .. } => {
+				for member in members {
+					add(&member.name, member.ty, &member.binding);
+				}
+			}
+			_ => add(&argument.name, argument.ty, &argument.binding)
+		}
+	}
+	inputs.sort_by_key(|input| input.location);
+	inputs
+}
+
+///The binding type of a global, along with its size if it's a buffer and its count if it's a binding array.\
+///Returns `None` for globals that aren't bound resources
+fn binding_type(module: &naga::Module, space: naga::AddressSpace, ty: naga::Handle<naga::Type>) -> Option<(wgpu::BindingType, Option<u64>, Option<NonZeroU32>)> {
+	let (ty, count) = match module.types[ty].inner {
+		naga::TypeInner::BindingArray { base, size } => (base, match size {
+			naga::ArraySize::Constant(count) => Some(count),
+			naga::ArraySize::Dynamic => None //Rejected by ShaderReflection::from_wgsl before getting here
+		}),
+		_ => (ty, None)
+	};
+	let size = u64::from(module.types[ty].inner.size(module.to_ctx()));
+
+	let buffer = |ty| wgpu::BindingType::Buffer {
+		ty,
+		has_dynamic_offset: false,
+		min_binding_size: NonZeroU64::new(size)
+	};
+	let binding_type = match space {
+		naga::AddressSpace::Uniform => return Some((buffer(wgpu::BufferBindingType::Uniform), Some(size), count)),
+		naga::AddressSpace::Storage { access } => {
+			let read_only = !access.contains(naga::StorageAccess::STORE);
+			return Some((buffer(wgpu::BufferBindingType::Storage { read_only }), Some(size), count));
+		}
+		naga::AddressSpace::Handle => match module.types[ty].inner {
+			naga::TypeInner::Sampler { comparison: true } => wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+			naga::TypeInner::Sampler { comparison: false } => wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+			naga::TypeInner::Image { dim, arrayed, class } => {
+				let view_dimension = view_dimension(dim, arrayed);
+				match class {
+					naga::ImageClass::Sampled { kind, multi } => wgpu::BindingType::Texture {
+						sample_type: match kind {
+							naga::ScalarKind::Sint => wgpu::TextureSampleType::Sint,
+							naga::ScalarKind::Uint => wgpu::TextureSampleType::Uint,
+							_ => wgpu::TextureSampleType::Float { filterable: true }
+						},
+						view_dimension,
+						multisampled: multi
+					},
+					naga::ImageClass::Depth { multi } => wgpu::BindingType::Texture {
+						sample_type: wgpu::TextureSampleType::Depth,
+						view_dimension,
+						multisampled: multi
+					},
+					naga::ImageClass::Storage { format, access } => wgpu::BindingType::StorageTexture {
+						access: match (access.contains(naga::StorageAccess::LOAD), access.contains(naga::StorageAccess::STORE)) {
+							(true, true) => wgpu::StorageTextureAccess::ReadWrite,
+							(true, false) => wgpu::StorageTextureAccess::ReadOnly,
+							(false, _) => wgpu::StorageTextureAccess::WriteOnly
+						},
+						format: storage_format(format),
+						view_dimension
+					}
+				}
+			}
+			_ => return None
+		},
+		_ => return None
+	};
+	Some((binding_type, None, count))
+}
+
+fn view_dimension(dim: naga::ImageDimension, arrayed: bool) -> wgpu::TextureViewDimension {
+	match (dim, arrayed) {
+		(naga::ImageDimension::D1, _) => wgpu::TextureViewDimension::D1,
+		(naga::ImageDimension::D2, false) => wgpu::TextureViewDimension::D2,
+		(naga::ImageDimension::D2, true) => wgpu::TextureViewDimension::D2Array,
+		(naga::ImageDimension::D3, _) => wgpu::TextureViewDimension::D3,
+		(naga::ImageDimension::Cube, false) => wgpu::TextureViewDimension::Cube,
+		(naga::ImageDimension::Cube, true) => wgpu::TextureViewDimension::CubeArray
+	}
+}
+
+fn storage_format(format: naga::StorageFormat) -> wgpu::TextureFormat {
+	use naga::StorageFormat as Sf;
+	use wgpu::TextureFormat as Tf;
+
+	match format {
+		Sf::R8Unorm => Tf::R8Unorm,
+		Sf::R8Snorm => Tf::R8Snorm,
+		Sf::R8Uint => Tf::R8Uint,
+		Sf::R8Sint => Tf::R8Sint,
+		Sf::R16Uint => Tf::R16Uint,
+		Sf::R16Sint => Tf::R16Sint,
+		Sf::R16Float => Tf::R16Float,
+		Sf::Rg8Unorm => Tf::Rg8Unorm,
+		Sf::Rg8Snorm => Tf::Rg8Snorm,
+		Sf::Rg8Uint => Tf::Rg8Uint,
+		Sf::Rg8Sint => Tf::Rg8Sint,
+		Sf::R32Uint => Tf::R32Uint,
+		Sf::R32Sint => Tf::R32Sint,
+		Sf::R32Float => Tf::R32Float,
+		Sf::Rg16Uint => Tf::Rg16Uint,
+		Sf::Rg16Sint => Tf::Rg16Sint,
+		Sf::Rg16Float => Tf::Rg16Float,
+		Sf::Rgba8Unorm => Tf::Rgba8Unorm,
+		Sf::Rgba8Snorm => Tf::Rgba8Snorm,
+		Sf::Rgba8Uint => Tf::Rgba8Uint,
+		Sf::Rgba8Sint => Tf::Rgba8Sint,
+		Sf::Bgra8Unorm => Tf::Bgra8Unorm,
+		Sf::Rgb10a2Uint => Tf::Rgb10a2Uint,
+		Sf::Rgb10a2Unorm => Tf::Rgb10a2Unorm,
+		Sf::Rg11b10Float => Tf::Rg11b10Float,
+		Sf::Rg32Uint => Tf::Rg32Uint,
+		Sf::Rg32Sint => Tf::Rg32Sint,
+		Sf::Rg32Float => Tf::Rg32Float,
+		Sf::Rgba16Uint => Tf::Rgba16Uint,
+		Sf::Rgba16Sint => Tf::Rgba16Sint,
+		Sf::Rgba16Float => Tf::Rgba16Float,
+		Sf::Rgba32Uint => Tf::Rgba32Uint,
+		Sf::Rgba32Sint => Tf::Rgba32Sint,
+		Sf::Rgba32Float => Tf::Rgba32Float,
+		Sf::R16Unorm => Tf::R16Unorm,
+		Sf::R16Snorm => Tf::R16Snorm,
+		Sf::Rg16Unorm => Tf::Rg16Unorm,
+		Sf::Rg16Snorm => Tf::Rg16Snorm,
+		Sf::Rgba16Unorm => Tf::Rgba16Unorm,
+		Sf::Rgba16Snorm => Tf::Rgba16Snorm
+	}
+}
@@ -0,0 +1,99 @@
+use heatwave::gpu::{BuddyAllocator, SubAllocation};
+
+#[test]
+fn allocations_are_aligned_to_their_block() {
+	let mut allocator = BuddyAllocator::new(1024, 16);
+
+	let small = allocator.allocate(10, 1).unwrap();
+	let large = allocator.allocate(100, 1).unwrap();
+	let aligned = allocator.allocate(8, 256).unwrap();
+
+	assert_eq!(small.offset, 0);
+	assert_eq!(large.offset, 128);
+	assert_eq!(aligned.offset, 256);
+}
+
+#[test]
+fn freed_blocks_merge_with_their_buddies() {
+	let mut allocator = BuddyAllocator::new(256, 16);
+	let allocations: Vec<SubAllocation> = (0..16).map(|_| allocator.allocate(16, 1).unwrap()).collect();
+	assert!(allocator.allocate(16, 1).is_none());
+
+	for allocation in allocations {
+		assert!(allocator.free(allocation));
+	}
+
+	assert!(allocator.is_empty());
+	assert_eq!(allocator.stats().largest_free_block, 256);
+	assert_eq!(allocator.allocate(256, 1), Some(SubAllocation { offset: 0, size: 256 }));
+}
+
+#[test]
+fn freeing_twice_is_rejected() {
+	let mut allocator = BuddyAllocator::new(256, 16);
+	let allocation = allocator.allocate(32, 1).unwrap();
+
+	assert!(allocator.free(allocation));
+	assert!(!allocator.free(allocation));
+	assert!(!allocator.free(SubAllocation { offset: 64, size: 32 }));
+}
+
+#[test]
+fn oversized_and_badly_aligned_allocations_fail() {
+	let mut allocator = BuddyAllocator::new(256, 16);
+
+	assert!(allocator.allocate(257, 1).is_none());
+	assert!(allocator.allocate(16, 3).is_none());
+	assert!(allocator.allocate(16, 512).is_none());
+}
+
+#[test]
+fn stats_track_usage_and_fragmentation() {
+	let mut allocator = BuddyAllocator::new(256, 16);
+	let first = allocator.allocate(16, 1).unwrap();
+	let _second = allocator.allocate(16, 1).unwrap();
+	let third = allocator.allocate(20, 1).unwrap();
+
+	let stats = allocator.stats();
+	assert_eq!(stats.allocations, 3);
+	assert_eq!(stats.requested, 52);
+	assert_eq!(stats.allocated, 64);
+	assert_eq!(stats.free, 192);
+	assert_eq!(stats.largest_free_block, 128);
+
+	allocator.free(first);
+	allocator.free(third);
+	let stats = allocator.stats();
+	assert_eq!(stats.free, 240);
+	assert_eq!(stats.largest_free_block, 128);
+	assert!((stats.fragmentation() - (1.0 - 128.0 / 240.0)).abs() < f32::EPSILON);
+}
+
+#[test]
+fn allocation_is_deterministic() {
+	let mut allocator = BuddyAllocator::new(4096, 16);
+	let live: Vec<SubAllocation> = [40, 300, 16, 1000, 64, 8, 500].into_iter().map(|size| allocator.allocate(size, 1).unwrap()).collect();
+	let offsets: Vec<u64> = live.iter().map(|allocation| allocation.offset).collect();
+	//Each allocation takes the lowest offset of the smallest free block that fits
+	assert_eq!(offsets, [0, 512, 64, 1024, 128, 80, 2048]);
+
+	assert!(allocator.free(live[1]));
+	assert!(allocator.free(live[4]));
+	//The freed 512 byte block is passed over for the lower 256 byte block, which was split off while making the first allocation
+	assert_eq!(allocator.allocate(200, 1).unwrap().offset, 256);
+	assert_eq!(allocator.allocate(100, 1).unwrap().offset, 128);
+	assert_eq!(allocator.allocate(300, 1).unwrap().offset, 512);
+}
+
+#[test]
+fn stale_allocations_are_rejected() {
+	let mut allocator = BuddyAllocator::new(256, 16);
+	let stale = allocator.allocate(32, 1).unwrap();
+	assert!(allocator.free(stale));
+	let reused = allocator.allocate(16, 1).unwrap();
+	assert_eq!(reused.offset, stale.offset);
+
+	assert!(!allocator.free(stale));
+	assert_eq!(allocator.stats().allocations, 1);
+	assert!(allocator.free(reused));
+}
@@ -0,0 +1,101 @@
+use heatwave::{
+	gpu::{ComputeDispatch, ComputeError, ComputePipelineHandle},
+	HeatwaveApp, HeatwaveConfig
+};
+
+///Squares every value in the buffer
+const SHADER: &str = "
+@group(0) @binding(0) var<storage, read_write> values: array<u32>;
+
+@compute @workgroup_size(1)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+	values[id.x] = values[id.x] * values[id.x];
+}";
+
+///Makes a headless app, or returns `None` if there's no adapter to run the test on
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	match pollster::block_on(HeatwaveApp::new(HeatwaveConfig { headless: true, ..Default::default() })) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+///Adds the squaring pipeline, along with the layout of the storage buffer it squares
+fn square_pipeline(app: &mut HeatwaveApp) -> (ComputePipelineHandle, wgpu::BindGroupLayout) {
+	let device = app.connection().device();
+	let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+		label: Some("Values Layout"),
+		entries: &[wgpu::BindGroupLayoutEntry {
+			binding: 0,
+			visibility: wgpu::ShaderStages::COMPUTE,
+			ty: wgpu::BindingType::Buffer {
+				ty: wgpu::BufferBindingType::Storage { read_only: false },
+				has_dynamic_offset: false,
+				min_binding_size: None
+			},
+			count: None
+		}]
+	});
+	let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+		label: Some("Square"),
+		source: wgpu::ShaderSource::Wgsl(SHADER.into())
+	});
+	let layout = app.add_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+		label: Some("Square Layout"),
+		bind_group_layouts: &[&bind_group_layout],
+		push_constant_ranges: &[]
+	});
+
+	let layout = app.pipeline_layout(layout).unwrap();
+	let pipeline = app.add_compute_pipeline(wgpu::ComputePipelineDescriptor {
+		label: Some("Square"),
+		layout: Some(&layout),
+		module: &module,
+		entry_point: "main"
+	}).unwrap();
+	(pipeline, bind_group_layout)
+}
+
+///Binds the whole buffer at binding 0 of the layout
+fn bind(app: &HeatwaveApp, layout: &wgpu::BindGroupLayout, buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+	app.connection().device().create_bind_group(&wgpu::BindGroupDescriptor {
+		label: Some("Values Group"),
+		layout,
+		entries: &[wgpu::BindGroupEntry { binding: 0, resource: buffer.as_entire_binding() }]
+	})
+}
+
+#[test]
+fn dispatches_are_read_back() {
+	let Some(mut app) = headless_app() else { return };
+	let (pipeline, layout) = square_pipeline(&mut app);
+	let values = app.add_buffer_from_slice(Some("Values"), &[1u32, 2, 3, 4], wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC);
+	let bind_group = bind(&app, &layout, app.buffer(values).unwrap());
+	let dispatch = ComputeDispatch { bind_groups: &[&bind_group], ..ComputeDispatch::new(pipeline, [4, 1, 1]) };
+
+	assert_eq!(app.compute(&dispatch, values), Ok(vec![1, 4, 9, 16]));
+	let readback = app.compute_async(&dispatch, values).unwrap();
+	assert_eq!(pollster::block_on(readback), Ok(vec![1, 16, 81, 256]));
+	assert!(app.poll_gpu_events().is_empty());
+}
+
+#[test]
+fn unaligned_outputs_are_rejected() {
+	let Some(mut app) = headless_app() else { return };
+	let (pipeline, layout) = square_pipeline(&mut app);
+	let values = app.add_buffer(wgpu::BufferDescriptor {
+		label: Some("Unaligned Values"),
+		size: 6,
+		usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+		mapped_at_creation: false
+	});
+	let bind_group = bind(&app, &layout, app.buffer(values).unwrap());
+	let dispatch = ComputeDispatch { bind_groups: &[&bind_group], ..ComputeDispatch::new(pipeline, [1, 1, 1]) };
+
+	let error = app.compute(&dispatch, values.cast::<u16>()).unwrap_err();
+	assert_eq!(error, ComputeError::UnalignedOutput { handle: values.raw(), size: 6 });
+	assert!(app.compute_async(&dispatch, values.cast::<u16>()).is_err());
+}
@@ -0,0 +1,107 @@
+use heatwave::{
+	gpu::{BufferHandle, GpuVec},
+	HeatwaveApp, HeatwaveConfig
+};
+
+///Makes a headless app, or returns `None` if there's no adapter to run the test on
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	match pollster::block_on(HeatwaveApp::new(HeatwaveConfig { headless: true, ..Default::default() })) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+///Copies the whole buffer back to the CPU
+fn read_buffer<T: bytemuck::Pod>(app: &HeatwaveApp, handle: BufferHandle<T>) -> Vec<T> {
+	let device = app.connection().device();
+	let buffer = app.buffer(handle).unwrap();
+	let staging = device.create_buffer(&wgpu::BufferDescriptor {
+		label: Some("Test Readback"),
+		size: buffer.size(),
+		usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+		mapped_at_creation: false
+	});
+	let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+	encoder.copy_buffer_to_buffer(buffer, 0, &staging, 0, buffer.size());
+	app.connection().queue().submit(Some(encoder.finish()));
+
+	staging.slice(..).map_async(wgpu::MapMode::Read, |result| result.unwrap());
+	device.poll(wgpu::Maintain::Wait);
+	let data = bytemuck::pod_collect_to_vec(&staging.slice(..).get_mapped_range());
+	data
+}
+
+#[test]
+fn dirty_ranges_merge_and_only_they_are_uploaded() {
+	let Some(mut app) = headless_app() else { return };
+	let mut values = GpuVec::<u32>::new(&mut app, Some("Values"), wgpu::BufferUsages::STORAGE, 8);
+	assert!(values.is_empty() && !values.is_dirty());
+
+	values.extend([1, 2, 3, 4, 5, 6]);
+	assert!(values.is_dirty());
+	values.sync(&mut app).unwrap();
+	assert!(!values.is_dirty());
+	assert_eq!(read_buffer(&app, values.handle()), [1, 2, 3, 4, 5, 6, 0, 0]);
+
+	//Stands in for a shader writing to the buffer. Elements outside the merged range keep what it wrote
+	app.write_buffer_slice(values.handle(), 0, &[10, 20, 30, 40, 50, 60]).unwrap();
+	values.set(1, 7);
+	values.set(3, 8);
+	values.sync(&mut app).unwrap();
+	assert_eq!(read_buffer(&app, values.handle()), [10, 7, 3, 8, 50, 60, 0, 0]);
+
+	//Truncating drops changes past the new end
+	values.push(9);
+	values.truncate(6);
+	assert!(!values.is_dirty());
+	values.sync(&mut app).unwrap();
+	assert_eq!(values.as_slice(), [1, 7, 3, 8, 5, 6]);
+}
+
+#[test]
+fn growing_reallocates_and_keeps_the_contents() {
+	let Some(mut app) = headless_app() else { return };
+	let mut values = GpuVec::<u32>::new(&mut app, Some("Values"), wgpu::BufferUsages::STORAGE, 2);
+	let handle = values.handle();
+
+	values.extend([1, 2]);
+	values.sync(&mut app).unwrap();
+	app.write_buffer_slice(values.handle(), 1, &[20]).unwrap();
+
+	//Pushing one past the capacity doubles it
+	values.push(3);
+	values.sync(&mut app).unwrap();
+	assert_eq!(values.capacity(), 4);
+	assert_eq!(values.handle(), handle);
+	assert_eq!(read_buffer(&app, values.handle()), [1, 20, 3, 0]);
+
+	//Extending past double the capacity grows to fit
+	values.extend(4..=10);
+	values.sync(&mut app).unwrap();
+	assert_eq!(values.capacity(), 10);
+	assert_eq!(values.len(), 10);
+	assert_eq!(read_buffer(&app, values.handle()), [1, 20, 3, 4, 5, 6, 7, 8, 9, 10]);
+	assert!(app.poll_gpu_events().is_empty());
+}
+
+#[test]
+fn unaligned_elements_are_widened_to_aligned_writes() {
+	let Some(mut app) = headless_app() else { return };
+	let mut indices = GpuVec::<u16>::new(&mut app, Some("Indices"), wgpu::BufferUsages::INDEX, 1);
+
+	indices.extend([1, 2, 3]);
+	indices.sync(&mut app).unwrap();
+	assert_eq!(indices.capacity(), 3);
+	//The buffer is rounded up to 4 bytes, and the space past the end of the list is written as zeros
+	assert_eq!(read_buffer(&app, indices.handle()), [1, 2, 3, 0]);
+
+	//Element 1 shares its aligned word with element 0, so both are written from the CPU copy
+	app.write_buffer_slice(indices.handle().cast::<u32>(), 0, &[u32::from_ne_bytes(bytemuck::cast([10u16, 20]))]).unwrap();
+	indices.set(1, 5);
+	indices.sync(&mut app).unwrap();
+	assert_eq!(read_buffer(&app, indices.handle()), [1, 5, 3, 0]);
+	assert!(app.poll_gpu_events().is_empty());
+}
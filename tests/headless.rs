@@ -0,0 +1,140 @@
+use heatwave::{
+	gpu::{GpuConnection, GpuConnectionErrorKind, RenderPipelineHandle, UniformRingConfig, UniformRingError},
+	rendering::{DrawCall, RenderError, RenderHelper, SimpleRenderPipelineDescriptor},
+	HeatwaveApp, HeatwaveConfig, HeatwaveInitialiseError
+};
+
+///Draws a red rectangle over the left half of the frame
+const SHADER: &str = "
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+	var corners = array<vec2<f32>, 6>(
+		vec2<f32>(-1.0, -1.0), vec2<f32>(0.0, -1.0), vec2<f32>(-1.0, 1.0),
+		vec2<f32>(-1.0, 1.0), vec2<f32>(0.0, -1.0), vec2<f32>(0.0, 1.0)
+	);
+	return vec4<f32>(corners[index], 0.5, 1.0);
+}
+
+@fragment
+fn fs_main() -> @location(0) vec4<f32> {
+	return vec4<f32>(1.0, 0.0, 0.0, 1.0);
+}";
+
+const RED: [u8; 4] = [255, 0, 0, 255];
+const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+///Makes a headless app drawing to a 4x2 texture with a blue background
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	let config = HeatwaveConfig {
+		headless: true,
+		default_size: winit::dpi::PhysicalSize::new(4, 2),
+		skybox: wgpu::Color::BLUE,
+		..Default::default()
+	};
+	match pollster::block_on(HeatwaveApp::new(config)) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+///Reads the offscreen texture back as one colour per pixel
+fn read_pixels(app: &HeatwaveApp) -> Vec<[u8; 4]> {
+	let pixels = app.connection().read_offscreen_pixels().expect("Expected a headless app to have readable pixels");
+	pixels.chunks_exact(4).map(|pixel| pixel.try_into().unwrap()).collect()
+}
+
+#[test]
+fn frames_are_cleared_to_the_background() {
+	let Some(app) = headless_app() else { return };
+
+	RenderHelper::new(&app).unwrap().render_with_pipelines(&[]).unwrap();
+
+	assert_eq!(read_pixels(&app), [BLUE; 8]);
+}
+
+///Adds the pipeline that draws the red rectangle
+fn rectangle_pipeline(app: &mut HeatwaveApp) -> RenderPipelineHandle {
+	let module = app.connection().device().create_shader_module(wgpu::ShaderModuleDescriptor {
+		label: Some("Half Rectangle"),
+		source: wgpu::ShaderSource::Wgsl(SHADER.into())
+	});
+	app.add_render_pipeline(SimpleRenderPipelineDescriptor {
+		name: "Half Rectangle",
+		vertex: &module,
+		fragment: Some(&module),
+		vertex_entry_point: "vs_main",
+		fragment_entry_point: Some("fs_main"),
+		vertex_buffer_format: &[]
+	}).unwrap()
+}
+
+#[test]
+fn draws_are_rendered_offscreen() {
+	let Some(mut app) = headless_app() else { return };
+	let pipeline = rectangle_pipeline(&mut app);
+
+	RenderHelper::new(&app).unwrap().render_with_pipelines(&[DrawCall::new(pipeline, 0..6)]).unwrap();
+
+	assert_eq!(read_pixels(&app), [RED, RED, BLUE, BLUE, RED, RED, BLUE, BLUE]);
+	assert!(app.poll_gpu_events().is_empty());
+}
+
+#[test]
+fn vertex_offsets_may_reach_the_end_of_the_buffer() {
+	let Some(mut app) = headless_app() else { return };
+	let pipeline = rectangle_pipeline(&mut app);
+	let empty = app.add_buffer(wgpu::BufferDescriptor { label: Some("Empty"), size: 0, usage: wgpu::BufferUsages::VERTEX, mapped_at_creation: false });
+	let full = app.add_buffer(wgpu::BufferDescriptor { label: Some("Full"), size: 16, usage: wgpu::BufferUsages::VERTEX, mapped_at_creation: false });
+	let buffers = [empty.raw(), full.raw()];
+	let draw = |offsets| DrawCall {
+		vertex_buffers: &buffers,
+		vertex_offsets: offsets,
+		..DrawCall::new(pipeline, 0..6)
+	};
+
+	RenderHelper::new(&app).unwrap().render_with_pipelines(&[draw(&[0, 16])]).unwrap();
+	assert_eq!(read_pixels(&app), [RED, RED, BLUE, BLUE, RED, RED, BLUE, BLUE]);
+	let error = RenderHelper::new(&app).unwrap().render_with_pipelines(&[draw(&[0, 17])]).unwrap_err();
+	assert_eq!(error, RenderError::OffsetOutOfRange(full.raw()));
+	assert!(app.poll_gpu_events().is_empty());
+}
+
+#[test]
+fn empty_offscreen_targets_are_rejected() {
+	let config = HeatwaveConfig {
+		headless: true,
+		default_size: winit::dpi::PhysicalSize::new(0, 16),
+		..Default::default()
+	};
+
+	let error = pollster::block_on(GpuConnection::new_headless(&config)).err().expect("Expected an empty offscreen target to be rejected");
+	assert!(matches!(error.kind(), GpuConnectionErrorKind::EmptyOffscreenTarget), "{error}");
+}
+
+#[test]
+fn uniform_rings_outside_the_device_limits_are_rejected() {
+	let create = |ring: UniformRingConfig| pollster::block_on(HeatwaveApp::new(HeatwaveConfig {
+		headless: true,
+		uniform_ring: Some(ring),
+		..Default::default()
+	}));
+
+	let unaligned = UniformRingConfig { binding_size: 257, ..Default::default() };
+	let too_large = UniformRingConfig { block_size: 1 << 40, ..Default::default() };
+	match create(unaligned).err() {
+		Some(HeatwaveInitialiseError::GpuConnection(error)) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			return;
+		}
+		Some(HeatwaveInitialiseError::UniformRing(error)) => assert!(matches!(error, UniformRingError::Unaligned { size: 257, .. }), "{error}"),
+		other => panic!("Expected an unaligned binding size to be rejected, got {other:?}")
+	}
+	match create(too_large).err() {
+		Some(HeatwaveInitialiseError::UniformRing(error)) => assert!(matches!(error, UniformRingError::ExceedsDeviceLimit { size: 0x100_0000_0000, .. }), "{error}"),
+		other => panic!("Expected an oversized block to be rejected, got {other:?}")
+	}
+	assert!(create(UniformRingConfig::default()).is_ok());
+}
@@ -0,0 +1,118 @@
+use heatwave::{
+	gpu::{ComputeDispatch, TextureHandle},
+	rendering::{ColourSpace, TextureOptions},
+	HeatwaveApp, HeatwaveConfig
+};
+use image::{Rgba, RgbaImage};
+
+///Copies the first texel of mip levels 0 and 1 into the output
+const SHADER: &str = "
+@group(0) @binding(0) var source: texture_2d<f32>;
+@group(0) @binding(1) var<storage, read_write> output: array<vec4<f32>, 2>;
+
+@compute @workgroup_size(1)
+fn main() {
+	output[0] = textureLoad(source, vec2<u32>(0u), 0);
+	output[1] = textureLoad(source, vec2<u32>(0u), 1);
+}";
+
+///Makes a headless app, or returns `None` if there's no adapter to run the test on
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	match pollster::block_on(HeatwaveApp::new(HeatwaveConfig { headless: true, ..Default::default() })) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+///Reads the first texel of the first two mip levels of the texture back to the CPU, in 0-255
+fn read_mips(app: &mut HeatwaveApp, texture: TextureHandle) -> Vec<[u8; 4]> {
+	let device = app.connection().device();
+	let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+		label: Some("Mip Readback Layout"),
+		entries: &[
+			wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: wgpu::ShaderStages::COMPUTE,
+				ty: wgpu::BindingType::Texture {
+					sample_type: wgpu::TextureSampleType::Float { filterable: true },
+					view_dimension: wgpu::TextureViewDimension::D2,
+					multisampled: false
+				},
+				count: None
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding: 1,
+				visibility: wgpu::ShaderStages::COMPUTE,
+				ty: wgpu::BindingType::Buffer {
+					ty: wgpu::BufferBindingType::Storage { read_only: false },
+					has_dynamic_offset: false,
+					min_binding_size: None
+				},
+				count: None
+			}
+		]
+	});
+	let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+		label: Some("Mip Readback"),
+		source: wgpu::ShaderSource::Wgsl(SHADER.into())
+	});
+	let layout = app.add_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+		label: Some("Mip Readback Layout"),
+		bind_group_layouts: &[&bind_group_layout],
+		push_constant_ranges: &[]
+	});
+	let layout = app.pipeline_layout(layout).unwrap();
+	let pipeline = app.add_compute_pipeline(wgpu::ComputePipelineDescriptor {
+		label: Some("Mip Readback"),
+		layout: Some(&layout),
+		module: &module,
+		entry_point: "main"
+	}).unwrap();
+	let output = app.add_buffer(wgpu::BufferDescriptor {
+		label: Some("Mip Readback"),
+		size: 32,
+		usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+		mapped_at_creation: false
+	}).cast::<[f32; 4]>();
+
+	let bind_group = app.connection().device().create_bind_group(&wgpu::BindGroupDescriptor {
+		label: Some("Mip Readback Group"),
+		layout: &bind_group_layout,
+		entries: &[
+			wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&app.texture(texture).unwrap().view) },
+			wgpu::BindGroupEntry { binding: 1, resource: app.buffer(output).unwrap().as_entire_binding() }
+		]
+	});
+	let texels = app.compute(&ComputeDispatch { bind_groups: &[&bind_group], ..ComputeDispatch::new(pipeline, [1, 1, 1]) }, output).unwrap();
+	texels.iter().map(|texel| texel.map(|channel| (channel * 255.0).round() as u8)).collect()
+}
+
+#[test]
+fn loaded_textures_have_their_mips_rendered() {
+	let Some(mut app) = headless_app() else { return };
+	let path = std::env::temp_dir().join(format!("heatwave_mipmaps_{}.png", std::process::id()));
+	let mut image = RgbaImage::new(2, 2);
+	for (pixel, red) in image.pixels_mut().zip([0, 100, 200, 100]) {
+		*pixel = Rgba([red, 50, 0, 255]);
+	}
+	image.save(&path).unwrap();
+
+	let options = TextureOptions {
+		colour_space: ColourSpace::Linear,
+		generate_mipmaps: true,
+		..Default::default()
+	};
+	//The second load reuses the pipeline made for the first
+	let first = app.load_texture(&path, &options).unwrap();
+	let second = app.load_texture(&path, &options).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	for handle in [first, second] {
+		assert_eq!(app.texture(handle).unwrap().texture.mip_level_count(), 2);
+		assert_eq!(read_mips(&mut app, handle), [[0, 50, 0, 255], [100, 50, 0, 255]]);
+	}
+	assert!(app.poll_gpu_events().is_empty());
+}
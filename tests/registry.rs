@@ -0,0 +1,30 @@
+use heatwave::{HeatwaveApp, HeatwaveConfig};
+
+///Makes a headless app, or returns `None` if there's no adapter to run the test on
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	match pollster::block_on(HeatwaveApp::new(HeatwaveConfig { headless: true, ..Default::default() })) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+#[test]
+fn shared_names_find_the_newest_remaining_resource() {
+	let Some(mut app) = headless_app() else { return };
+	let descriptor = wgpu::SamplerDescriptor { label: Some("Shared"), ..Default::default() };
+	let first = app.add_sampler(&descriptor);
+	let second = app.add_sampler(&descriptor);
+	let third = app.add_sampler(&descriptor);
+	assert_eq!(app.samplers().find("Shared"), Some(third));
+
+	app.remove_sampler(second);
+	assert_eq!(app.samplers().find("Shared"), Some(third));
+	app.remove_sampler(third);
+	assert_eq!(app.samplers().find("Shared"), Some(first));
+	assert_eq!(app.samplers().name(first), Some("Shared"));
+	app.remove_sampler(first);
+	assert_eq!(app.samplers().find("Shared"), None);
+}
@@ -0,0 +1,186 @@
+use std::{
+	collections::HashMap,
+	io,
+	path::{Path, PathBuf}
+};
+
+use heatwave::rendering::{DirectiveError, PreprocessError, PreprocessedShader, ShaderPreprocessor, SourceLocation};
+
+///Processes the first file, reading every file from memory
+fn process(preprocessor: &ShaderPreprocessor, files: &[(&str, &str)]) -> Result<PreprocessedShader, PreprocessError> {
+	let root = files[0].0;
+	let files: HashMap<PathBuf, &str> = files.iter().map(|(path, source)| (PathBuf::from(path), *source)).collect();
+	preprocessor.process_with(root, |path| {
+		files.get(path).map(|source| source.to_string()).ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+	})
+}
+
+fn location(path: &str, line: usize) -> SourceLocation {
+	SourceLocation { path: PathBuf::from(path), line }
+}
+
+#[test]
+fn includes_are_inserted_once_relative_to_the_including_file() {
+	let shader = process(
+		&ShaderPreprocessor::new(),
+		&[
+			("main.wgsl", "#include \"lib/lighting.wgsl\"\n#include \"lib/math.wgsl\"\nfn main() {}"),
+			("lib/lighting.wgsl", "#include \"./math.wgsl\"\nfn light() {}"),
+			("lib/math.wgsl", "fn square() {}")
+		]
+	)
+	.unwrap();
+
+	assert_eq!(shader.source(), "fn square() {}\nfn light() {}\nfn main() {}\n");
+	assert_eq!(shader.files(), [PathBuf::from("main.wgsl"), PathBuf::from("lib/lighting.wgsl"), PathBuf::from("lib/math.wgsl")]);
+}
+
+#[test]
+fn comments_are_only_stripped_outside_include_paths() {
+	let shader = process(
+		&ShaderPreprocessor::new(),
+		&[
+			("main.wgsl", "#include \"lib//math.wgsl\" // Squares numbers\nfn main() {}"),
+			("lib/math.wgsl", "fn square() {}")
+		]
+	)
+	.unwrap();
+
+	assert_eq!(shader.source(), "fn square() {}\nfn main() {}\n");
+	assert_eq!(shader.files(), [PathBuf::from("main.wgsl"), PathBuf::from("lib/math.wgsl")]);
+}
+
+#[test]
+fn include_cycles_are_reported() {
+	let error = process(
+		&ShaderPreprocessor::new(),
+		&[
+			("main.wgsl", "#include \"lib/a.wgsl\""),
+			("lib/a.wgsl", "#include \"b.wgsl\""),
+			("lib/b.wgsl", "\n#include \"../lib/a.wgsl\"")
+		]
+	)
+	.unwrap_err();
+
+	match error {
+		PreprocessError::IncludeCycle { chain, location: at } => {
+			assert_eq!(chain, [PathBuf::from("lib/a.wgsl"), PathBuf::from("lib/b.wgsl"), PathBuf::from("lib/a.wgsl")]);
+			assert_eq!(at, location("lib/b.wgsl", 2));
+		}
+		error => panic!("Expected an include cycle, got {error}")
+	}
+}
+
+#[test]
+fn missing_includes_report_where_they_were_included() {
+	let error = process(&ShaderPreprocessor::new(), &[("main.wgsl", "fn main() {}\n#include \"missing.wgsl\"")]).unwrap_err();
+
+	match error {
+		PreprocessError::Read { path, included_from, .. } => {
+			assert_eq!(path, Path::new("missing.wgsl"));
+			assert_eq!(included_from, Some(location("main.wgsl", 2)));
+		}
+		error => panic!("Expected a read error, got {error}")
+	}
+}
+
+#[test]
+fn defines_replace_whole_words() {
+	let shader = process(
+		&ShaderPreprocessor::new().define("MAX_LIGHTS", "8"),
+		&[("main.wgsl", "#define SCALE 2.0\n#define DOUBLE_LIGHTS MAX_LIGHTS * 2 // Expanded when defined\nconst a = MAX_LIGHTS;\nconst b = SCALE * 1e5 + MAX_LIGHTS_X;\nconst c = DOUBLE_LIGHTS;")],
+	)
+	.unwrap();
+
+	assert_eq!(shader.source(), "const a = 8;\nconst b = 2.0 * 1e5 + MAX_LIGHTS_X;\nconst c = 8 * 2;\n");
+}
+
+#[test]
+fn conditionals_follow_features_and_defines() {
+	let source = "#ifdef SHADOWS\nshadows\n    #ifndef SOFT\n    hard\n    #else\n    soft\n    #endif\n#else\nno_shadows\n#endif\n#define FOG\n#ifdef FOG\nfog\n#endif";
+
+	let shader = process(&ShaderPreprocessor::new().feature("SHADOWS"), &[("main.wgsl", source)]).unwrap();
+	assert_eq!(shader.source(), "shadows\n    hard\nfog\n");
+
+	let shader = process(&ShaderPreprocessor::new().feature("SHADOWS").feature("SOFT"), &[("main.wgsl", source)]).unwrap();
+	assert_eq!(shader.source(), "shadows\n    soft\nfog\n");
+
+	let shader = process(&ShaderPreprocessor::new().feature("SOFT"), &[("main.wgsl", source)]).unwrap();
+	assert_eq!(shader.source(), "no_shadows\nfog\n");
+}
+
+#[test]
+fn directives_in_removed_blocks_are_ignored() {
+	let shader = process(
+		&ShaderPreprocessor::new(),
+		&[("main.wgsl", "#ifdef MISSING\n#include \"missing.wgsl\"\n#define VALUE 1\n#endif\nconst a = VALUE;")]
+	)
+	.unwrap();
+
+	assert_eq!(shader.source(), "const a = VALUE;\n");
+}
+
+#[test]
+fn malformed_directives_are_reported() {
+	let directive_error = |source: &str| match process(&ShaderPreprocessor::new(), &[("main.wgsl", source)]) {
+		Err(PreprocessError::Directive { location, kind }) => (location.line, kind),
+		result => panic!("Expected a directive error, got {result:?}")
+	};
+
+	assert_eq!(directive_error("#version 450"), (1, DirectiveError::Unknown("version".to_string())));
+	assert_eq!(directive_error("#include main.wgsl"), (1, DirectiveError::Malformed("include".to_string())));
+	assert_eq!(directive_error("#define 2X 4"), (1, DirectiveError::Malformed("define".to_string())));
+	assert_eq!(directive_error("#ifdef"), (1, DirectiveError::Malformed("ifdef".to_string())));
+	assert_eq!(directive_error("\n#endif"), (2, DirectiveError::Unmatched("endif".to_string())));
+	assert_eq!(directive_error("#ifdef A\n#else\n#else\n#endif"), (3, DirectiveError::Unmatched("else".to_string())));
+	assert_eq!(directive_error("#ifdef A\n#ifdef B\n#endif"), (1, DirectiveError::Unterminated));
+}
+
+#[test]
+fn conditionals_cannot_span_files() {
+	let error = process(
+		&ShaderPreprocessor::new(),
+		&[("main.wgsl", "#include \"open.wgsl\"\n#endif"), ("open.wgsl", "#ifdef A")]
+	)
+	.unwrap_err();
+
+	assert!(matches!(error, PreprocessError::Directive { location: at, kind: DirectiveError::Unterminated } if at == location("open.wgsl", 1)));
+}
+
+#[test]
+fn lines_map_back_to_their_files() {
+	let shader = process(
+		&ShaderPreprocessor::new(),
+		&[
+			("main.wgsl", "#include \"lighting.wgsl\"\n\nfn main() {}"),
+			("lighting.wgsl", "#ifdef SHADOWS\nfn shadow() {}\n#endif\nfn light() {}")
+		]
+	)
+	.unwrap();
+
+	assert_eq!(shader.location(1), Some(location("lighting.wgsl", 4)));
+	assert_eq!(shader.location(2), Some(location("main.wgsl", 2)));
+	assert_eq!(shader.location(3), Some(location("main.wgsl", 3)));
+	assert_eq!(shader.location(0), None);
+	assert_eq!(shader.location(4), None);
+}
+
+#[test]
+fn naga_errors_point_at_the_original_file() {
+	let shader = process(
+		&ShaderPreprocessor::new(),
+		&[
+			("main.wgsl", "#include \"lib/lighting.wgsl\"\nfn main() {}"),
+			("lib/lighting.wgsl", "fn light() -> f32 {\n#ifdef SHADOWS\n    return 0.5;\n#endif\n    return missing;\n}")
+		]
+	)
+	.unwrap();
+
+	let error = wgpu::naga::front::wgsl::parse_str(shader.source()).unwrap_err().emit_to_string(shader.source());
+	assert!(error.contains("wgsl:2:12"), "{error}");
+
+	let mapped = shader.map_error(&error);
+	let expected = format!("{}:12", location("lib/lighting.wgsl", 5));
+	assert!(mapped.contains(&expected), "{mapped}");
+	assert!(!mapped.contains("wgsl:2:12"), "{mapped}");
+}
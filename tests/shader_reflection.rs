@@ -0,0 +1,305 @@
+use std::num::{NonZeroU32, NonZeroU64};
+
+use heatwave::rendering::{
+	ReflectionError, ShaderObject, ShaderReflection, VertexInput, VertexInputType, VertexLayoutError, VertexScalar
+};
+
+const SHADER: &str = "
+struct Camera {
+	view_projection: mat4x4<f32>,
+	position: vec3<f32>,
+}
+struct VertexInput {
+	@location(0) position: vec3<f32>,
+	@location(2) colour: vec4<f32>,
+}
+struct VertexOutput {
+	@builtin(position) position: vec4<f32>,
+	@location(0) colour: vec4<f32>,
+	@location(1) uv: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> camera: Camera;
+@group(1) @binding(0) var albedo: texture_2d<f32>;
+@group(1) @binding(1) var albedo_sampler: sampler;
+@group(1) @binding(2) var shadow_map: texture_depth_2d_array;
+@group(1) @binding(3) var shadow_sampler: sampler_comparison;
+@group(2) @binding(0) var<storage, read> lights: array<vec4<f32>>;
+@group(2) @binding(1) var<storage, read_write> counters: array<atomic<u32>, 4>;
+@group(2) @binding(2) var output: texture_storage_2d<rgba16float, write>;
+@group(3) @binding(5) var unused: texture_cube<u32>;
+
+@vertex
+fn vs_main(input: VertexInput, @location(1) uv: vec2<f32>, @builtin(vertex_index) index: u32) -> VertexOutput {
+	var output: VertexOutput;
+	output.position = camera.view_projection * vec4<f32>(input.position, 1.0);
+	output.colour = input.colour;
+	output.uv = uv;
+	return output;
+}
+
+@fragment
+fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
+	let shadow = textureSampleCompare(shadow_map, shadow_sampler, input.uv, 0, 0.5);
+	return textureSample(albedo, albedo_sampler, input.uv) * input.colour * shadow + vec4<f32>(camera.position, 0.0);
+}
+
+@compute @workgroup_size(8, 4)
+fn cs_main(@builtin(global_invocation_id) id: vec3<u32>) {
+	atomicAdd(&counters[id.x % 4u], u32(lights[id.x].x));
+	textureStore(output, id.xy, vec4<f32>(1.0));
+}
+";
+
+#[repr(C)]
+struct Vertex {
+	_position: [f32; 3],
+	_uv: [f32; 2],
+	_colour: [u8; 4]
+}
+impl Vertex {
+	const ATTRIBUTES: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Unorm8x4];
+}
+impl ShaderObject for Vertex {
+	fn layout() -> wgpu::VertexBufferLayout<'static> {
+		wgpu::VertexBufferLayout {
+			array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
+			step_mode: wgpu::VertexStepMode::Vertex,
+			attributes: &Self::ATTRIBUTES
+		}
+	}
+}
+
+fn input(location: u32, name: &str, scalar: VertexScalar, components: u32) -> VertexInput {
+	VertexInput {
+		location,
+		name: Some(name.to_string()),
+		ty: VertexInputType { scalar, components }
+	}
+}
+
+#[test]
+fn entry_points_are_listed_with_their_stages() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+	let entry_points: Vec<(&str, wgpu::ShaderStages)> = reflection.entry_points().iter().map(|entry_point| (entry_point.name.as_str(), entry_point.stage)).collect();
+	assert_eq!(
+		entry_points,
+		[("vs_main", wgpu::ShaderStages::VERTEX), ("fs_main", wgpu::ShaderStages::FRAGMENT), ("cs_main", wgpu::ShaderStages::COMPUTE)]
+	);
+	assert_eq!(reflection.entry_point("cs_main").unwrap().workgroup_size, [8, 4, 1]);
+	assert!(reflection.entry_point("missing").is_none());
+}
+
+#[test]
+fn vertex_inputs_include_struct_members_and_skip_builtins() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+	assert_eq!(
+		reflection.entry_point("vs_main").unwrap().vertex_inputs,
+		[input(0, "position", VertexScalar::F32, 3), input(1, "uv", VertexScalar::F32, 2), input(2, "colour", VertexScalar::F32, 4)]
+	);
+	assert!(reflection.entry_point("fs_main").unwrap().vertex_inputs.is_empty());
+}
+
+#[test]
+fn buffers_have_their_sizes_and_access() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+	let bindings = reflection.bindings();
+
+	assert_eq!(bindings[0].name.as_deref(), Some("camera"));
+	assert_eq!(bindings[0].size, Some(80));
+	assert_eq!(bindings[0].visibility, wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT);
+	assert_eq!(
+		bindings[0].ty,
+		wgpu::BindingType::Buffer {
+			ty: wgpu::BufferBindingType::Uniform,
+			has_dynamic_offset: false,
+			min_binding_size: NonZeroU64::new(80)
+		}
+	);
+
+	let lights = bindings.iter().find(|binding| binding.name.as_deref() == Some("lights")).unwrap();
+	assert_eq!((lights.group, lights.binding, lights.size), (2, 0, Some(16)));
+	assert_eq!(lights.visibility, wgpu::ShaderStages::COMPUTE);
+	assert!(matches!(lights.ty, wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Storage { read_only: true }, .. }));
+
+	let counters = bindings.iter().find(|binding| binding.name.as_deref() == Some("counters")).unwrap();
+	assert_eq!(counters.size, Some(16));
+	assert!(matches!(counters.ty, wgpu::BindingType::Buffer { ty: wgpu::BufferBindingType::Storage { read_only: false }, .. }));
+}
+
+#[test]
+fn textures_and_samplers_have_their_types() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+	let entries = reflection.bind_group_layout_entries(1);
+
+	assert_eq!(
+		entries,
+		[
+			wgpu::BindGroupLayoutEntry {
+				binding: 0,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Texture {
+					sample_type: wgpu::TextureSampleType::Float { filterable: true },
+					view_dimension: wgpu::TextureViewDimension::D2,
+					multisampled: false
+				},
+				count: None
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding: 1,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+				count: None
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding: 2,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Texture {
+					sample_type: wgpu::TextureSampleType::Depth,
+					view_dimension: wgpu::TextureViewDimension::D2Array,
+					multisampled: false
+				},
+				count: None
+			},
+			wgpu::BindGroupLayoutEntry {
+				binding: 3,
+				visibility: wgpu::ShaderStages::FRAGMENT,
+				ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+				count: None
+			}
+		]
+	);
+
+	let output = &reflection.bind_group_layout_entries(2)[2];
+	assert_eq!(
+		output.ty,
+		wgpu::BindingType::StorageTexture {
+			access: wgpu::StorageTextureAccess::WriteOnly,
+			format: wgpu::TextureFormat::Rgba16Float,
+			view_dimension: wgpu::TextureViewDimension::D2
+		}
+	);
+}
+
+#[test]
+fn unused_bindings_have_no_visibility() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+	assert_eq!(reflection.bind_group_count(), 4);
+	let unused = reflection.bind_group_layout_entries(3);
+	assert_eq!(unused.len(), 1);
+	assert_eq!(unused[0].binding, 5);
+	assert_eq!(unused[0].visibility, wgpu::ShaderStages::NONE);
+	assert!(matches!(
+		unused[0].ty,
+		wgpu::BindingType::Texture { sample_type: wgpu::TextureSampleType::Uint, view_dimension: wgpu::TextureViewDimension::Cube, .. }
+	));
+}
+
+#[test]
+fn binding_arrays_and_push_constants_are_found() {
+	let reflection = ShaderReflection::from_wgsl(
+		"
+struct Constants { tint: vec4<f32>, scale: f32 }
+var<push_constant> constants: Constants;
+@group(0) @binding(0) var textures: binding_array<texture_2d<f32>, 8>;
+@group(0) @binding(1) var texture_sampler: sampler;
+
+@vertex fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+	return vec4<f32>(constants.scale);
+}
+@fragment fn fs_main() -> @location(0) vec4<f32> {
+	return textureSample(textures[2], texture_sampler, vec2<f32>(0.5)) * constants.tint;
+}",
+	)
+	.unwrap();
+
+	assert_eq!(
+		reflection.push_constants(),
+		[wgpu::PushConstantRange {
+			stages: wgpu::ShaderStages::VERTEX_FRAGMENT,
+			range: 0..32
+		}]
+	);
+	assert_eq!(reflection.bindings()[0].count, NonZeroU32::new(8));
+	assert_eq!(reflection.bindings()[1].count, None);
+}
+
+#[test]
+fn unbounded_binding_arrays_are_rejected() {
+	let error = ShaderReflection::from_wgsl(
+		"
+@group(1) @binding(3) var textures: binding_array<texture_2d<f32>>;
+@group(1) @binding(4) var texture_sampler: sampler;
+
+@fragment fn fs_main() -> @location(0) vec4<f32> {
+	return textureSample(textures[0], texture_sampler, vec2<f32>(0.5));
+}",
+	)
+	.unwrap_err();
+
+	assert_eq!(
+		error,
+		ReflectionError::UnboundedBindingArray {
+			group: 1,
+			binding: 3,
+			name: Some("textures".to_string())
+		}
+	);
+}
+
+#[test]
+fn matching_shader_objects_pass() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+
+	assert_eq!(reflection.check_shader_object::<Vertex>("vs_main"), Ok(()));
+}
+
+#[test]
+fn drifted_vertex_layouts_are_caught() {
+	let reflection = ShaderReflection::from_wgsl(SHADER).unwrap();
+	let check = |attributes: &[wgpu::VertexAttribute]| {
+		reflection.check_vertex_layout(
+			"vs_main",
+			&[wgpu::VertexBufferLayout {
+				array_stride: 36,
+				step_mode: wgpu::VertexStepMode::Vertex,
+				attributes
+			}]
+		)
+	};
+
+	assert_eq!(
+		check(&wgpu::vertex_attr_array![0 => Float32x3, 2 => Float32x4]),
+		Err(VertexLayoutError::MissingAttribute { location: 1, name: Some("uv".to_string()) })
+	);
+	assert_eq!(
+		check(&wgpu::vertex_attr_array![0 => Float32x2, 1 => Float32x2, 2 => Float32x4]),
+		Err(VertexLayoutError::FormatMismatch {
+			location: 0,
+			format: wgpu::VertexFormat::Float32x2,
+			expected: VertexInputType { scalar: VertexScalar::F32, components: 3 }
+		})
+	);
+	assert!(matches!(
+		check(&wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Uint8x4]),
+		Err(VertexLayoutError::FormatMismatch { location: 2, .. })
+	));
+	//Attributes the shader doesn't read are fine
+	assert_eq!(check(&wgpu::vertex_attr_array![0 => Float32x3, 1 => Float32x2, 2 => Float32x4, 3 => Uint32]), Ok(()));
+	assert_eq!(
+		reflection.check_shader_object::<Vertex>("fs_main"),
+		Err(VertexLayoutError::UnknownEntryPoint("fs_main".to_string()))
+	);
+}
+
+#[test]
+fn invalid_shaders_are_rejected() {
+	let parse = ShaderReflection::from_wgsl("fn main( {}").unwrap_err();
+	assert!(matches!(&parse, ReflectionError::Parse(message) if message.contains("wgsl:1:")), "{parse}");
+
+	let validation = ShaderReflection::from_wgsl("@fragment fn fs_main() -> @location(0) vec4<f32> { return 1.0; }").unwrap_err();
+	assert!(matches!(validation, ReflectionError::Validation(_)), "{validation}");
+}
@@ -0,0 +1,124 @@
+use std::{
+	error::Error,
+	fs::File,
+	io,
+	path::PathBuf,
+	time::{Duration, SystemTime}
+};
+
+use heatwave::{
+	gpu::{ComputeDispatch, GpuErrorKind, ShaderFileErrorKind},
+	HeatwaveApp, HeatwaveConfig, HeatwaveError
+};
+
+const SHADER: &str = "
+@group(0) @binding(0) var<storage, read_write> output: array<u32>;
+
+@compute @workgroup_size(1)
+fn main() {
+	output[0] = VALUE;
+}";
+
+///Makes a headless app, or returns `None` if there's no adapter to run the test on
+fn headless_app() -> Option<HeatwaveApp<'static>> {
+	match pollster::block_on(HeatwaveApp::new(HeatwaveConfig { headless: true, ..Default::default() })) {
+		Ok(app) => Some(app),
+		Err(error) => {
+			eprintln!("Skipping, no headless device is available: {error}");
+			None
+		}
+	}
+}
+
+///Writes the shader with `value` stored in the output, marking the file as modified at `modified`
+fn write_shader(path: &PathBuf, value: u32, modified: SystemTime) {
+	std::fs::write(path, SHADER.replace("VALUE", &format!("{value}u"))).unwrap();
+	File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn pipelines_with_registered_layouts_are_reloaded() {
+	let Some(mut app) = headless_app() else { return };
+	let path = std::env::temp_dir().join(format!("heatwave_reload_{}.wgsl", std::process::id()));
+	let loaded = SystemTime::now();
+	write_shader(&path, 1, loaded);
+	let shader = app.load_shader(&path).unwrap();
+
+	let device = app.connection().device();
+	let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+		label: Some("Output Layout"),
+		entries: &[wgpu::BindGroupLayoutEntry {
+			binding: 0,
+			visibility: wgpu::ShaderStages::COMPUTE,
+			ty: wgpu::BindingType::Buffer {
+				ty: wgpu::BufferBindingType::Storage { read_only: false },
+				has_dynamic_offset: false,
+				min_binding_size: None
+			},
+			count: None
+		}]
+	});
+	let layout_descriptor = wgpu::PipelineLayoutDescriptor {
+		label: Some("Output Pipeline Layout"),
+		bind_group_layouts: &[&bind_group_layout],
+		push_constant_ranges: &[]
+	};
+	let unregistered_layout = device.create_pipeline_layout(&layout_descriptor);
+	let layout = app.add_pipeline_layout(&layout_descriptor);
+
+	let output = app.add_buffer(wgpu::BufferDescriptor {
+		label: Some("Output"),
+		size: 4,
+		usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+		mapped_at_creation: false
+	}).cast::<u32>();
+	let bind_group = app.connection().device().create_bind_group(&wgpu::BindGroupDescriptor {
+		label: Some("Output Group"),
+		layout: &bind_group_layout,
+		entries: &[wgpu::BindGroupEntry { binding: 0, resource: app.buffer(output).unwrap().as_entire_binding() }]
+	});
+
+	let module = app.shader(shader).unwrap();
+	let registered_layout = app.pipeline_layout(layout).unwrap();
+	let pipeline = app.add_compute_pipeline(wgpu::ComputePipelineDescriptor {
+		label: Some("Registered Layout"),
+		layout: Some(&registered_layout),
+		module: &module,
+		entry_point: "main"
+	}).unwrap();
+	let stale = app.add_compute_pipeline(wgpu::ComputePipelineDescriptor {
+		label: Some("Unregistered Layout"),
+		layout: Some(&unregistered_layout),
+		module: &module,
+		entry_point: "main"
+	}).unwrap();
+	let stale_id = app.compute_pipeline(stale).unwrap().global_id();
+
+	let run = |app: &HeatwaveApp, pipeline| app.compute(&ComputeDispatch { bind_groups: &[&bind_group], ..ComputeDispatch::new(pipeline, [1, 1, 1]) }, output).unwrap();
+	assert_eq!(run(&app, pipeline), [1]);
+
+	write_shader(&path, 2, loaded + Duration::from_secs(10));
+	let reloaded = app.reload_shaders();
+	std::fs::remove_file(&path).unwrap();
+
+	assert_eq!(reloaded, [Ok(shader)]);
+	assert_eq!(run(&app, pipeline), [2]);
+	//The pipeline with a layout the app doesn't know about can't be made again, so keeps the old shader
+	assert_eq!(app.compute_pipeline(stale).unwrap().global_id(), stale_id);
+	assert_eq!(run(&app, stale), [1]);
+}
+
+#[test]
+fn shader_file_errors_keep_their_sources() {
+	let Some(mut app) = headless_app() else { return };
+	let path = std::env::temp_dir().join(format!("heatwave_reload_errors_{}.wgsl", std::process::id()));
+
+	let missing = HeatwaveError::from(app.load_shader(&path).unwrap_err());
+	let read = missing.source().and_then(Error::source).and_then(|source| source.downcast_ref::<io::Error>()).expect("Expected the io error in the source chain");
+	assert_eq!(read.kind(), io::ErrorKind::NotFound);
+
+	std::fs::write(&path, "fn main( {}").unwrap();
+	let error = app.load_shader(&path).unwrap_err();
+	std::fs::remove_file(&path).unwrap();
+	assert!(matches!(&error.kind, ShaderFileErrorKind::Compile(compile) if compile.kind == GpuErrorKind::Validation), "{error}");
+}
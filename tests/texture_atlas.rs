@@ -0,0 +1,124 @@
+use heatwave::rendering::{AtlasConfig, AtlasError, AtlasPacker, AtlasRect, TextureAtlas};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+fn solid(width: u32, height: u32, colour: [u8; 4]) -> DynamicImage {
+	DynamicImage::ImageRgba8(RgbaImage::from_pixel(width, height, Rgba(colour)))
+}
+
+#[test]
+fn packer_fills_rows_from_the_top_left() {
+	let mut packer = AtlasPacker::new(64, 64);
+
+	assert_eq!(packer.pack(32, 16), Some(AtlasRect { x: 0, y: 0, width: 32, height: 16 }));
+	assert_eq!(packer.pack(32, 8), Some(AtlasRect { x: 32, y: 0, width: 32, height: 8 }));
+	assert_eq!(packer.pack(16, 8), Some(AtlasRect { x: 32, y: 8, width: 16, height: 8 }));
+	assert_eq!(packer.pack(64, 8), Some(AtlasRect { x: 0, y: 16, width: 64, height: 8 }));
+}
+
+#[test]
+fn packer_rejects_what_does_not_fit() {
+	let mut packer = AtlasPacker::new(64, 64);
+
+	assert!(packer.pack(65, 1).is_none());
+	assert!(packer.pack(0, 10).is_none());
+	assert!(packer.pack(64, 64).is_some());
+	assert!(packer.pack(1, 1).is_none());
+}
+
+#[test]
+fn packed_rects_never_overlap_and_packing_is_deterministic() {
+	let run = || {
+		let mut packer = AtlasPacker::new(256, 256);
+		let mut rects = Vec::new();
+		for index in 0..200u32 {
+			let width = 3 + (index * 7) % 29;
+			let height = 2 + (index * 13) % 23;
+			if let Some(rect) = packer.pack(width, height) {
+				rects.push(rect);
+			}
+		}
+		rects
+	};
+	let rects = run();
+
+	assert!(rects.len() > 50);
+	for (index, rect) in rects.iter().enumerate() {
+		assert!(rect.x + rect.width <= 256 && rect.y + rect.height <= 256);
+		assert!(rects[index + 1..].iter().all(|other| !rect.overlaps(other)));
+	}
+	assert_eq!(rects, run());
+}
+
+#[test]
+fn entries_are_padded_and_extruded() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig { page_size: 64, padding: 2, extrusion: 1, ..Default::default() });
+	let red = atlas.add("red", &solid(4, 4, [255, 0, 0, 255])).unwrap();
+	let blue = atlas.add("blue", &solid(4, 4, [0, 0, 255, 255])).unwrap();
+
+	assert_eq!(red.rect, AtlasRect { x: 1, y: 1, width: 4, height: 4 });
+	assert_eq!(blue.rect, AtlasRect { x: 9, y: 1, width: 4, height: 4 });
+
+	let page = atlas.page_image(0).unwrap();
+	//Extrusion repeats the edge pixels around each entry, including the corners
+	assert_eq!(page.get_pixel(0, 0), &Rgba([255, 0, 0, 255]));
+	assert_eq!(page.get_pixel(5, 3), &Rgba([255, 0, 0, 255]));
+	assert_eq!(page.get_pixel(8, 5), &Rgba([0, 0, 255, 255]));
+	//Padding is left empty between the extruded entries
+	assert_eq!(page.get_pixel(6, 3), &Rgba([0, 0, 0, 0]));
+	assert_eq!(page.get_pixel(7, 3), &Rgba([0, 0, 0, 0]));
+}
+
+#[test]
+fn uv_rects_cover_only_the_image() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig { page_size: 64, padding: 0, extrusion: 2, ..Default::default() });
+	let entry = atlas.add("sprite", &solid(16, 8, [255; 4])).unwrap();
+
+	assert_eq!(entry.uv.min, [2.0 / 64.0, 2.0 / 64.0]);
+	assert_eq!(entry.uv.max, [18.0 / 64.0, 10.0 / 64.0]);
+	assert_eq!(atlas.get("sprite"), Some(entry));
+}
+
+#[test]
+fn entries_spill_onto_new_pages() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig { page_size: 32, padding: 0, extrusion: 0, ..Default::default() });
+	for index in 0..5 {
+		let entry = atlas.add(&index.to_string(), &solid(16, 16, [255; 4])).unwrap();
+		assert_eq!(entry.page, index / 4);
+	}
+
+	assert_eq!(atlas.page_count(), 2);
+	assert_eq!(atlas.len(), 5);
+	assert!(atlas.page_texture(0).is_none());
+}
+
+#[test]
+fn entries_can_be_added_incrementally() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig { page_size: 64, ..Default::default() });
+	let first = atlas.add("first", &solid(8, 8, [255; 4])).unwrap();
+	let second = atlas.add("second", &solid(8, 8, [255; 4])).unwrap();
+
+	assert_eq!(atlas.get("first"), Some(first));
+	assert!(!first.rect.overlaps(&second.rect));
+	assert!(matches!(atlas.add("first", &solid(8, 8, [255; 4])), Err(AtlasError::NameTaken(_))));
+	assert_eq!(atlas.len(), 2);
+}
+
+#[test]
+fn images_larger_than_a_page_are_rejected() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig { page_size: 32, padding: 4, extrusion: 1, ..Default::default() });
+
+	assert!(atlas.add("fits", &solid(30, 30, [255; 4])).is_ok());
+	assert!(matches!(atlas.add("too big", &solid(31, 30, [255; 4])), Err(AtlasError::TooLarge { width: 33, height: 32, page_size: 32 })));
+	assert_eq!(atlas.len(), 1);
+}
+
+#[test]
+fn empty_images_are_rejected() {
+	let mut atlas = TextureAtlas::new(None, AtlasConfig::default());
+	let mut unpadded = TextureAtlas::new(None, AtlasConfig { padding: 0, extrusion: 0, ..Default::default() });
+
+	assert!(matches!(atlas.add("empty", &solid(0, 0, [255; 4])), Err(AtlasError::Empty { width: 0, height: 0 })));
+	assert!(matches!(unpadded.add("empty", &solid(0, 0, [255; 4])), Err(AtlasError::Empty { width: 0, height: 0 })));
+	assert!(matches!(unpadded.add("line", &solid(4, 0, [255; 4])), Err(AtlasError::Empty { width: 4, height: 0 })));
+	assert!(atlas.is_empty() && unpadded.is_empty());
+}